@@ -0,0 +1,30 @@
+/// A possible event in a sequence
+pub trait Block {}
+
+/// A single line of dialogue, optionally attributed to a speaker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dialogue {
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+impl Block for Dialogue {}
+
+/// A `let` binding: the named slot and the offset into the VM bytecode chunk
+/// shared by every script where its initializer begins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetBinding {
+    pub name: String,
+    pub offset: usize,
+}
+
+impl Block for LetBinding {}
+
+/// A bare Petrel expression statement, compiled straight into the owning
+/// script's shared VM bytecode chunk, starting at `offset`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PetrelChunk {
+    pub offset: usize,
+}
+
+impl Block for PetrelChunk {}
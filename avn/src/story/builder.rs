@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use petgraph::stable_graph::StableGraph;
+use petrel::compiler::ast::{ScriptDecl, Stmt};
+use thiserror::Error;
+
+use super::block::{Dialogue, LetBinding, PetrelChunk};
+use super::{Block, Path, Sequence, Story};
+
+/// Errors produced while lowering a parsed script AST into a [`Story`].
+#[derive(Debug, Error)]
+pub enum BuildError {
+    #[error("script {0:?} jumps to undefined label {1:?}")]
+    UndefinedJumpTarget(String, String),
+    #[error("script {0:?} calls undefined label {1:?}")]
+    UndefinedCallTarget(String, String),
+    #[error("script {0:?} is declared more than once")]
+    DuplicateScript(String),
+}
+
+/// Lower a parsed script AST (see
+/// [`Compiler::parse_scripts`](petrel::compiler::Compiler::parse_scripts))
+/// into a [`Story`] graph: each script becomes a [`Sequence`] node, `jump`/
+/// `call` statements become [`Path::LoadPoint`] edges into the named
+/// script's node, and a script falls through via a [`Path::Continue`] edge
+/// to whichever script is declared immediately after it.
+///
+/// Two passes, so a script can `jump`/`call` one declared later in the
+/// file: the first registers every `Sequence` node by name, the second
+/// resolves edge targets. Every jump/call to an undefined label is
+/// reported - the whole batch, not just the first one - instead of
+/// aborting the build.
+pub fn build_story(scripts: &[ScriptDecl]) -> Result<Story, Vec<BuildError>> {
+    let mut tree = StableGraph::new();
+    let mut nodes = HashMap::new();
+    let mut entry = None;
+    let mut errors = vec![];
+
+    // Pass 1: register every `Sequence` node by name before resolving any
+    // edges, so forward references work. A duplicate name is reported and
+    // otherwise ignored - the first declaration wins, and nothing after it
+    // overwrites its node - rather than silently rewiring every jump/call to
+    // whichever declaration happened to be inserted last.
+    for script in scripts {
+        let node = tree.add_node(Sequence::new(lower_events(&script.body)));
+        if nodes.contains_key(&script.name) {
+            errors.push(BuildError::DuplicateScript(script.name.clone()));
+            continue;
+        }
+        nodes.insert(script.name.clone(), node);
+        if script.is_entry {
+            entry = Some(node);
+        }
+    }
+
+    // Pass 2: resolve jump/call targets and link sequential fallthrough.
+    for (i, script) in scripts.iter().enumerate() {
+        let from = nodes[&script.name];
+
+        for stmt in &script.body {
+            match stmt {
+                Stmt::Jump(target) => match nodes.get(target) {
+                    Some(&to) => {
+                        tree.add_edge(from, to, Path::LoadPoint(target.clone()));
+                    }
+                    None => errors.push(BuildError::UndefinedJumpTarget(
+                        script.name.clone(),
+                        target.clone(),
+                    )),
+                },
+                Stmt::Call(target) => match nodes.get(target) {
+                    Some(&to) => {
+                        tree.add_edge(from, to, Path::LoadPoint(target.clone()));
+                    }
+                    None => errors.push(BuildError::UndefinedCallTarget(
+                        script.name.clone(),
+                        target.clone(),
+                    )),
+                },
+                _ => {}
+            }
+        }
+
+        if let Some(next) = scripts.get(i + 1) {
+            tree.add_edge(from, nodes[&next.name], Path::Continue);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(Story::new(tree, entry))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Lower a script body's statements into concrete `Block` events. `Jump`/
+/// `Call` don't produce events - they're resolved into graph edges above -
+/// so they're the only statements filtered out here.
+fn lower_events(body: &[Stmt]) -> Vec<Box<dyn Block>> {
+    body.iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Dialogue(d) => Some(Box::new(Dialogue {
+                speaker: d.speaker.clone(),
+                text: d.text.clone(),
+            }) as Box<dyn Block>),
+            Stmt::Let(l) => Some(Box::new(LetBinding {
+                name: l.name.clone(),
+                offset: l.offset,
+            }) as Box<dyn Block>),
+            Stmt::PetrelBlock(offset) => {
+                Some(Box::new(PetrelChunk { offset: *offset }) as Box<dyn Block>)
+            }
+            Stmt::Jump(_) | Stmt::Call(_) => None,
+        })
+        .collect()
+}
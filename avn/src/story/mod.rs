@@ -1,20 +1,54 @@
-use petgraph::stable_graph::StableGraph;
+mod block;
+mod builder;
+
+use petgraph::stable_graph::{NodeIndex, StableGraph};
+
+pub use block::{Block, Dialogue, LetBinding, PetrelChunk};
+pub use builder::{build_story, BuildError};
 
 /// Represents the path between sequences.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Path {
     LoadPoint(String),
     Continue,
 }
 
-/// A possible event in a sequence
-pub trait Block {}
-
-/// A sequence of events that take place one after the other 
+/// A sequence of events that take place one after the other
 pub struct Sequence {
     events: Vec<Box<dyn Block>>,
 }
 
+impl Sequence {
+    pub(crate) fn new(events: Vec<Box<dyn Block>>) -> Self {
+        Self { events }
+    }
+
+    /// The events that make up this sequence, in order.
+    pub fn events(&self) -> &[Box<dyn Block>] {
+        &self.events
+    }
+}
+
 /// The overall story of the visual novel
 pub struct Story {
-    tree: StableGraph<Sequence, Path>
-}
\ No newline at end of file
+    tree: StableGraph<Sequence, Path>,
+    entry: Option<NodeIndex>,
+}
+
+impl Story {
+    pub(crate) fn new(tree: StableGraph<Sequence, Path>, entry: Option<NodeIndex>) -> Self {
+        Self { tree, entry }
+    }
+
+    /// The `Sequence` node a runtime should start walking the graph from:
+    /// whichever script was declared with a leading `start` keyword, if any.
+    pub fn entry(&self) -> Option<NodeIndex> {
+        self.entry
+    }
+
+    /// The underlying graph, for a runtime to walk `Path` edges between
+    /// `Sequence` nodes.
+    pub fn tree(&self) -> &StableGraph<Sequence, Path> {
+        &self.tree
+    }
+}
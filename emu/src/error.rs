@@ -8,4 +8,6 @@ pub enum EmuError {
     UnexpectedEOF,
     #[error("unknown symbol {0}")]
     UnknownSymbol(char),
+    #[error("unterminated string starting at line {line}, column {column}")]
+    UnterminatedString { line: usize, column: usize },
 }
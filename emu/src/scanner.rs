@@ -68,7 +68,7 @@ impl Scanner {
             match c {
                 // This effectivly translates everything on the opposite side to a string
                 '-' => {
-                    let dialogue = self.consume_until_char('\n');
+                    let dialogue = self.consume_dialogue();
                     Ok(self.make_token(String(dialogue)))
                 }
                 '[' => {
@@ -82,7 +82,7 @@ impl Scanner {
                 // { } acts as a *insert petrel block* and hence is effectivly a string but with petrel code instead
                 '{' => Ok(self.petrel()),
                 // String
-                '"' => Ok(self.string()),
+                '"' => self.string(),
                 '@' => {
                     self.next();
                     Ok(self.make_token(At))
@@ -233,6 +233,103 @@ impl Scanner {
         }
     }
 
+    /// Like [`Scanner::consume_until_char`], but for a string literal:
+    /// decodes backslash escapes (`\n`, `\t`, `\"`, `\\`, and `\{` so a
+    /// string can embed a literal brace without it reading as a Petrel
+    /// block) instead of copying them verbatim, tracks `line` across any raw
+    /// newline consumed along the way, and reports
+    /// `EmuError::UnterminatedString` - pointing at where the literal opened
+    /// - instead of silently returning a truncated token when we run out of
+    /// input before `end`. Unlike dialogue (see
+    /// [`Scanner::consume_dialogue`]), running out of input here really is
+    /// an error: a string has to be closed by its own `end` delimiter, not
+    /// just fall off the end of the file.
+    fn consume_escaped_until_char(&mut self, end: char) -> Result<String, EmuError> {
+        let start_line = self.line;
+        let start_column = self.column;
+        // Go past the opening character
+        self.next().expect("Unreachable");
+
+        let unterminated = || EmuError::UnterminatedString {
+            line: start_line,
+            column: start_column,
+        };
+
+        let mut consumed = String::from("");
+        loop {
+            let c = *self.source.peek().ok_or_else(unterminated)?;
+            if c == end {
+                // Go past the closing delimiter
+                self.next().expect("Unreachable");
+                break;
+            }
+
+            if c == '\\' {
+                self.next().expect("Unreachable");
+                let escaped = *self.source.peek().ok_or_else(unterminated)?;
+                consumed.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    '"' => '"',
+                    '\\' => '\\',
+                    '{' => '{',
+                    other => other,
+                });
+                self.next().expect("Unreachable");
+                continue;
+            }
+
+            if c == '\n' {
+                self.line += 1;
+            }
+            consumed.push(c);
+            self.next().expect("Unreachable");
+        }
+
+        Ok(consumed)
+    }
+
+    /// Like [`Scanner::consume_escaped_until_char`], but for dialogue, which
+    /// is terminated by `'\n'` *or* end of file - the last line of a file
+    /// with no trailing newline is a normal dialogue line, not an error.
+    fn consume_dialogue(&mut self) -> String {
+        // Go past the opening '-'
+        self.next().expect("Unreachable");
+
+        let mut consumed = String::from("");
+        while let Some(&c) = self.source.peek() {
+            if c == '\n' {
+                break;
+            }
+
+            if c == '\\' {
+                self.next().expect("Unreachable");
+                match self.source.peek() {
+                    Some(&escaped) => {
+                        consumed.push(match escaped {
+                            'n' => '\n',
+                            't' => '\t',
+                            '"' => '"',
+                            '\\' => '\\',
+                            '{' => '{',
+                            other => other,
+                        });
+                        self.next().expect("Unreachable");
+                    }
+                    // A trailing backslash with nothing after it at end of
+                    // file: keep it verbatim rather than dropping it.
+                    None => consumed.push('\\'),
+                }
+                continue;
+            }
+
+            consumed.push(c);
+            self.next().expect("Unreachable");
+        }
+
+        consumed
+    }
+
     /// Create a comment
     fn comment(&mut self) {
         // Consume all the characters until we reach a new line
@@ -246,10 +343,10 @@ impl Scanner {
         self.make_token(TokenType::Petrel(petrel))
     }
 
-    fn string(&mut self) -> Token {
+    fn string(&mut self) -> Result<Token, EmuError> {
         // Consume until the closing "
-        let string = self.consume_until_char('"');
-        self.make_token(TokenType::String(string))
+        let string = self.consume_escaped_until_char('"')?;
+        Ok(self.make_token(TokenType::String(string)))
     }
 
     /// Make a token
@@ -318,4 +415,37 @@ mod tests {
         ];
         assert_eq!(token_types, result);
     }
+
+    #[test]
+    fn string_literal_decodes_escapes() {
+        let mut scanner = super::Scanner::new(r#""a\nb\tc\"d\\e\{f""#.to_string());
+        let tokens = scanner.scan().expect("failed to scan tokens");
+        use crate::token::TokenType;
+        let token_types: Vec<TokenType> = tokens.into_iter().map(|t| t.tt).collect();
+        let result = vec![
+            TokenType::String("a\nb\tc\"d\\e{f".to_string()),
+            TokenType::EOF,
+        ];
+        assert_eq!(token_types, result);
+    }
+
+    #[test]
+    fn final_dialogue_line_without_trailing_newline_is_not_an_error() {
+        let mut scanner = super::Scanner::new("-hello".to_string());
+        let tokens = scanner.scan().expect("failed to scan tokens");
+        use crate::token::TokenType;
+        let token_types: Vec<TokenType> = tokens.into_iter().map(|t| t.tt).collect();
+        let result = vec![TokenType::String("hello".to_string()), TokenType::EOF];
+        assert_eq!(token_types, result);
+    }
+
+    #[test]
+    fn unterminated_string_is_an_error() {
+        let mut scanner = super::Scanner::new(r#""unterminated"#.to_string());
+        let err = scanner.scan().expect_err("should have failed to scan");
+        assert!(matches!(
+            err,
+            crate::error::EmuError::UnterminatedString { line: 1, column: 1 }
+        ));
+    }
 }
@@ -0,0 +1,85 @@
+//! Generates `OUT_DIR/opcode_gen.rs` from `instructions.in`.
+//!
+//! The VM used to hand-write the `Opcode` enum, its byte conversions, and
+//! the disassembler's mnemonic table in three separate places, and they had
+//! already drifted apart. This build script makes `instructions.in` the
+//! single source of truth: adding an opcode is a one-line edit here instead
+//! of updating the enum, both `TryFrom`/`From` impls, and the disassembler
+//! by hand.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+struct Instruction {
+    name: String,
+    operand_len: u8,
+}
+
+fn parse_instructions(src: &str) -> Vec<Instruction> {
+    src.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts
+                .next()
+                .unwrap_or_else(|| panic!("instructions.in: missing opcode name in {line:?}"))
+                .to_string();
+            let operand_len = parts
+                .next()
+                .unwrap_or_else(|| panic!("instructions.in: missing operand length for {name}"))
+                .parse()
+                .unwrap_or_else(|_| panic!("instructions.in: operand length for {name} must be a number"));
+            Instruction { name, operand_len }
+        })
+        .collect()
+}
+
+fn generate(instructions: &[Instruction]) -> String {
+    let mut out = String::new();
+
+    out.push_str("// @generated by build.rs from instructions.in. Do not edit by hand.\n\n");
+
+    out.push_str("#[derive(Debug)]\n#[repr(u8)]\npub enum Opcode {\n");
+    for inst in instructions {
+        let _ = writeln!(out, "    {},", inst.name);
+    }
+    out.push_str("}\n\n");
+
+    out.push_str("impl TryFrom<u8> for Opcode {\n    type Error = VMError;\n    fn try_from(src: u8) -> Result<Self, Self::Error> {\n        match src {\n");
+    for (code, inst) in instructions.iter().enumerate() {
+        let _ = writeln!(out, "            {code} => Ok(Opcode::{}),", inst.name);
+    }
+    out.push_str("            _ => Err(VMError::InvalidOpcodeConversion(src)),\n        }\n    }\n}\n\n");
+
+    out.push_str("impl From<Opcode> for u8 {\n    fn from(code: Opcode) -> Self {\n        code as u8\n    }\n}\n\n");
+
+    out.push_str("impl Opcode {\n    /// Number of `Operation` slots immediately following this opcode that belong to it.\n    pub fn operand_len(&self) -> usize {\n        match self {\n");
+    for inst in instructions {
+        let _ = writeln!(out, "            Opcode::{} => {},", inst.name, inst.operand_len);
+    }
+    out.push_str("        }\n    }\n\n");
+
+    out.push_str("    /// The mnemonic used by the disassembler.\n    pub fn mnemonic(&self) -> &'static str {\n        match self {\n");
+    for inst in instructions {
+        let mnemonic = inst.name.strip_prefix("Op").unwrap_or(&inst.name);
+        let _ = writeln!(out, "            Opcode::{} => \"{}\",", inst.name, mnemonic);
+    }
+    out.push_str("        }\n    }\n}\n");
+
+    out
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=instructions.in");
+
+    let src = fs::read_to_string("instructions.in").expect("failed to read instructions.in");
+    let instructions = parse_instructions(&src);
+    let generated = generate(&instructions);
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let dest = Path::new(&out_dir).join("opcode_gen.rs");
+    fs::write(dest, generated).expect("failed to write opcode_gen.rs");
+}
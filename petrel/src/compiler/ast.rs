@@ -0,0 +1,49 @@
+//! Typed AST produced by [`Compiler::parse_scripts`](super::compiler::Compiler::parse_scripts),
+//! built against the grammar in `grammar.ebnf` at the crate root. A later
+//! pass walks this to build the `Story` graph (see `avn::story`) instead of
+//! re-parsing tokens.
+
+/// `ScriptDecl = ["start"] "script" Identifier "{" {Statement} "}"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptDecl {
+    pub name: String,
+    /// Whether this script was declared with a leading `start` keyword,
+    /// marking it the `Story`'s entry point.
+    pub is_entry: bool,
+    pub body: Vec<Stmt>,
+}
+
+/// `Statement = LetStmt | JumpStmt | CallStmt | DialogueStmt | PetrelBlock`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Let(LetStmt),
+    /// `JumpStmt = "jump" Identifier`, holding the target script's name.
+    Jump(String),
+    /// `CallStmt = "call" Identifier`, holding the target script's name.
+    Call(String),
+    Dialogue(DialogueStmt),
+    /// `PetrelBlock = Expression`. The expression itself is compiled
+    /// straight to VM bytecode by `parse_precidence`, the same way a bare
+    /// expression script always has been, so there's nothing further to
+    /// keep here beyond the offset into `VM::instructions` where that
+    /// compiled bytecode begins, for a later pass (see `avn::story`) to
+    /// stitch into a graph node without re-parsing.
+    PetrelBlock(usize),
+}
+
+/// `LetStmt = "let" Identifier "=" Expression`. The initializer is compiled
+/// straight to bytecode, same as `PetrelBlock`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetStmt {
+    pub name: String,
+    /// Offset into `VM::instructions` where the initializer's compiled
+    /// bytecode begins.
+    pub offset: usize,
+}
+
+/// `DialogueStmt = [ Identifier ":" ] String`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialogueStmt {
+    pub speaker: Option<String>,
+    pub text: String,
+}
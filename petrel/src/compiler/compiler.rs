@@ -1,3 +1,5 @@
+use super::ast::{DialogueStmt, LetStmt, ScriptDecl, Stmt};
+use super::cursor::TokenCursor;
 use super::{Token, TokenType};
 use crate::common::Value;
 use crate::diagnostic::{Annotation, PetrelError};
@@ -62,16 +64,14 @@ impl Default for ParseRule {
 /// for the [`VM`]
 ///
 /// ## Panics
-/// The compiler has to traverse a vec of tokens and panics when it cannot look up a token that should be there
-/// or when it can't convert a string to a literal
+/// The compiler panics when it can't convert a string to a literal; token
+/// navigation itself is panic-free (see [`TokenCursor`])
 #[derive(Debug)]
 pub struct Compiler {
     /// The source as a list of lines
     pub source: String,
-    /// Tokens :)
-    pub tokens: Vec<Token>,
-    /// Index
-    index: usize,
+    /// Tokens, and our position within them
+    cursor: TokenCursor,
     /// The virtual machine we're writing our instructions to
     pub vm: VM,
     /// Collected errors
@@ -85,70 +85,89 @@ impl Compiler {
         let src = source.lines().map(|s| s.to_string()).collect();
         Self {
             source: src,
-            tokens,
-            index: 0,
+            cursor: TokenCursor::new(tokens),
             vm: VM::new(),
             errors: vec![],
             panicMode: false,
         }
     }
 
-    pub fn compile(&mut self) -> &mut VM {
+    /// Compile a bare expression (a `script`/`start`-less source is just one
+    /// `PetrelBlock`) into bytecode. A syntax error doesn't abort the
+    /// compile outright: `report_error` records it into `self.errors` and
+    /// synchronizes past it, so this only fails once compilation is done,
+    /// returning the whole batch instead of just the first mistake.
+    pub fn compile(&mut self) -> Result<&mut VM, &[PetrelError]> {
         self.expression();
-        self.consume(TokenType::EOF)
-            .expect("Expected end of expression");
-        // As consuming the EOF increases our index beyond range
-        self.index -= 1;
+        // Already recorded into `self.errors` by `report_error` if it failed.
+        let _ = self.consume(TokenType::EOF);
         self.add_instruction(Opcode::OpReturn);
-        &mut self.vm
-    }
-
-    /// Advance the index by 1 and return the character
-    #[inline]
-    fn next(&mut self) -> Option<&Token> {
-        self.index += 1;
-        self.tokens.get(self.index)
-    }
 
-    /// Returns the previous token.
-    ///
-    /// ## Panics
-    /// Panics when it can't find the token. This function should always return and if its called at
-    /// the start then it's being missused and panics.
-    fn previous(&self) -> &Token {
-        self.tokens
-            .get(self.index - 1)
-            .expect("Should always be a previous token")
+        if self.errors.is_empty() {
+            Ok(&mut self.vm)
+        } else {
+            Err(&self.errors)
+        }
     }
 
-    /// Advance the index by 1
+    /// Consume the current token.
     #[inline]
     fn advance(&mut self) {
-        self.index += 1;
+        self.cursor.advance();
     }
 
-    /// Peek at next char without consuming the character
+    /// The most recently consumed token.
     #[inline]
-    fn peek(&mut self) -> Option<&Token> {
-        self.tokens.get(self.index + 1)
+    fn previous(&self) -> &Token {
+        self.cursor.previous()
     }
 
-    /// Get the current character.
-    ///
-    /// ## Panics
-    /// Should never fail so it panics if the index out of bounds
+    /// The token at the cursor, without consuming it.
     #[inline]
     fn current(&self) -> &Token {
-        self.tokens
-            .get(self.index)
-            .expect("Current token out of range.")
+        self.cursor.current()
+    }
+
+    /// The `n`th token ahead of the cursor, without consuming anything.
+    #[inline]
+    fn lookahead(&self, n: usize) -> &Token {
+        self.cursor.lookahead(n)
     }
 
-    /// Used to report error. Mostly exists so I don't forget to set panic mode to true.
+    /// Report and recover from a compile error, rustc-parser style: push it
+    /// into `self.errors`, then synchronize to the next statement boundary
+    /// so the caller can keep parsing instead of aborting the whole
+    /// compile. The `Err` this returns carries no information of its own —
+    /// `self.errors` is the actual record of what went wrong — it only
+    /// signals the `?` chain to unwind the abandoned statement/expression.
     #[inline]
     fn report_error(&mut self, code_error: PetrelError) -> Result<(), PetrelError> {
         self.panicMode = true;
-        Err(code_error)
+        self.errors.push(code_error);
+        self.synchronize();
+        Err(PetrelError::Recovering)
+    }
+
+    /// Advance past tokens until a safe point to resume parsing: an `NL`
+    /// (ending whatever dialogue/statement the error happened in), the
+    /// start of a new declaration (`start`/`script`/`jump`/`call`/`let`),
+    /// or `EOF`; then clear panic mode. The request this implements also
+    /// lists `@` as a boundary, but no such token exists anywhere in this
+    /// tokenizer, so it's omitted here.
+    fn synchronize(&mut self) {
+        while !matches!(
+            self.current().tt,
+            TokenType::NL
+                | TokenType::Start
+                | TokenType::Script
+                | TokenType::Jump
+                | TokenType::Call
+                | TokenType::Let
+                | TokenType::EOF
+        ) {
+            self.advance();
+        }
+        self.panicMode = false;
     }
 
     /// Used to create an annotation for error reporting
@@ -279,12 +298,13 @@ impl Compiler {
         Ok(())
     }
 
-    /// Parse an expression
+    /// Parse an expression. A syntax error inside it has already been
+    /// pushed into `self.errors` and synchronized past by the time
+    /// `parse_precidence` returns `Err`, so there's nothing left to do
+    /// here but stop.
     #[inline]
     fn expression(&mut self) {
-        if let Err(e) = self.parse_precidence(Precedence::Assignment) {
-            panic!("{}", e)
-        }
+        let _ = self.parse_precidence(Precedence::Assignment);
     }
 
     /// Add an instruction to the vm
@@ -296,10 +316,168 @@ impl Compiler {
     /// Add a constant to the vm
     fn add_constant(&mut self, value: Value) {
         let current_line = self.current().line;
-        let rf = self.vm.write_constant(value);
-        self.vm
-            .write_operation(Opcode::OpConstant.into(), current_line);
-        self.vm.write_operation(rf, current_line);
+        self.vm.write_constant_op(value, current_line);
+    }
+
+    /// True if the current token is `tt`, without consuming it.
+    #[inline]
+    fn check(&self, tt: TokenType) -> bool {
+        self.current().tt == tt
+    }
+
+    /// Consume the current token if it's `tt`, reporting whether it matched.
+    #[inline]
+    fn match_token(&mut self, tt: TokenType) -> bool {
+        if self.check(tt) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Text of the most recently consumed token, sliced out of `self.source`.
+    fn previous_text(&self) -> String {
+        let previous = self.previous();
+        self.source
+            .get(previous.start..(previous.start + previous.length))
+            .expect("token should reference valid source text")
+            .to_string()
+    }
+
+    /// Consume an `Identifier` token and return the name it spells out.
+    fn identifier_text(&mut self) -> Result<String, PetrelError> {
+        self.consume(TokenType::Identifier)?;
+        Ok(self.previous_text())
+    }
+
+    /// Skip a run of `NL` tokens, e.g. blank lines between statements.
+    fn skip_newlines(&mut self) {
+        while self.match_token(TokenType::NL) {}
+    }
+
+    /// End a statement: require an `NL` unless the stream has already hit
+    /// `}` or EOF, then skip any further blank lines.
+    fn end_statement(&mut self) -> Result<(), PetrelError> {
+        if !matches!(self.current().tt, TokenType::RightBrace | TokenType::EOF) {
+            self.consume(TokenType::NL)?;
+        }
+        self.skip_newlines();
+        Ok(())
+    }
+
+    /// Parse every top-level `ScriptDecl` in the token stream into a typed
+    /// AST, per `grammar.ebnf`'s `Grammar = {ScriptDecl}` rule. Unlike
+    /// [`Compiler::compile`], this doesn't drive the VM directly — only the
+    /// `PetrelBlock`/`LetStmt` expressions nested inside a script do, via
+    /// the existing Pratt parser; the rest becomes AST for a later pass to
+    /// compile into the `Story` graph.
+    ///
+    /// A malformed `ScriptDecl` doesn't stop the whole parse: its error is
+    /// recorded and the stream is synchronized past it (see
+    /// [`Compiler::report_error`]), so every later script still gets a
+    /// chance. `Err` is only returned once parsing is done, carrying every
+    /// error collected along the way.
+    pub fn parse_scripts(&mut self) -> Result<Vec<ScriptDecl>, &[PetrelError]> {
+        let mut scripts = vec![];
+        self.skip_newlines();
+        while !self.check(TokenType::EOF) {
+            let before = self.cursor.position();
+            match self.script_decl() {
+                Ok(script) => scripts.push(script),
+                Err(_) => {
+                    // Already recorded and synchronized; if that left the
+                    // cursor exactly where it started (the bad token also
+                    // happened to look like a sync point), force one token
+                    // of progress so this can't spin forever.
+                    if self.cursor.position() == before && !self.check(TokenType::EOF) {
+                        self.advance();
+                    }
+                }
+            }
+            self.skip_newlines();
+        }
+
+        if self.errors.is_empty() {
+            Ok(scripts)
+        } else {
+            Err(&self.errors)
+        }
+    }
+
+    /// `ScriptDecl = ["start"] "script" Identifier "{" {Statement} "}"`
+    fn script_decl(&mut self) -> Result<ScriptDecl, PetrelError> {
+        let is_entry = self.match_token(TokenType::Start);
+        self.consume(TokenType::Script)?;
+        let name = self.identifier_text()?;
+        self.consume(TokenType::LeftBrace)?;
+        self.skip_newlines();
+
+        let mut body = vec![];
+        while !matches!(self.current().tt, TokenType::RightBrace | TokenType::EOF) {
+            let before = self.cursor.position();
+            match self.statement() {
+                Ok(stmt) => body.push(stmt),
+                Err(_) => {
+                    if self.cursor.position() == before
+                        && !matches!(self.current().tt, TokenType::RightBrace | TokenType::EOF)
+                    {
+                        self.advance();
+                    }
+                }
+            }
+        }
+        self.consume(TokenType::RightBrace)?;
+
+        Ok(ScriptDecl {
+            name,
+            is_entry,
+            body,
+        })
+    }
+
+    /// `Statement = (LetStmt | JumpStmt | CallStmt | DialogueStmt | PetrelBlock) NL`
+    fn statement(&mut self) -> Result<Stmt, PetrelError> {
+        let stmt = if self.match_token(TokenType::Let) {
+            Stmt::Let(self.let_stmt()?)
+        } else if self.match_token(TokenType::Jump) {
+            Stmt::Jump(self.identifier_text()?)
+        } else if self.match_token(TokenType::Call) {
+            Stmt::Call(self.identifier_text()?)
+        } else if self.check(TokenType::String)
+            || (self.check(TokenType::Identifier) && self.lookahead(1).tt == TokenType::Colon)
+        {
+            Stmt::Dialogue(self.dialogue_stmt()?)
+        } else {
+            let offset = self.vm.instructions.len();
+            self.expression();
+            Stmt::PetrelBlock(offset)
+        };
+        self.end_statement()?;
+        Ok(stmt)
+    }
+
+    /// `LetStmt = "let" Identifier "=" Expression`
+    fn let_stmt(&mut self) -> Result<LetStmt, PetrelError> {
+        let name = self.identifier_text()?;
+        self.consume(TokenType::Equal)?;
+        let offset = self.vm.instructions.len();
+        self.expression();
+        Ok(LetStmt { name, offset })
+    }
+
+    /// `DialogueStmt = [ Identifier ":" ] String`
+    fn dialogue_stmt(&mut self) -> Result<DialogueStmt, PetrelError> {
+        let speaker = if self.check(TokenType::Identifier) {
+            let name = self.identifier_text()?;
+            self.consume(TokenType::Colon)?;
+            Some(name)
+        } else {
+            None
+        };
+        self.consume(TokenType::String)?;
+        let text = self.previous_text();
+        Ok(DialogueStmt { speaker, text })
     }
 }
 
@@ -314,16 +492,136 @@ mod compiler_test {
             .expect("Failed to create scanner");
         let tks = scanner.scan().expect("Scanning failed");
         let mut compiler = Compiler::new(scanner.source.iter().collect(), tks);
-        compiler.compile().run(true).expect("VM failed to run");
+        compiler
+            .compile()
+            .expect("compile should have succeeded")
+            .run(true)
+            .expect("VM failed to run");
     }
 
     #[test]
-    #[should_panic]
     fn syntax_error() {
         let mut scanner = Scanner::from_file("./scripts/tests/syntax_error.ptrl")
             .expect("Failed to create scanner");
         let tks = scanner.scan().expect("Scanning failed");
         let mut compiler = Compiler::new(scanner.source.iter().collect(), tks);
-        compiler.compile();
+        // `compile` no longer panics on the first mistake: it synchronizes
+        // past it and reports the whole batch through `Err` instead.
+        assert!(compiler.compile().is_err());
+    }
+}
+
+#[cfg(test)]
+mod parse_scripts_test {
+    use super::super::ast::{DialogueStmt, LetStmt, ScriptDecl, Stmt};
+    use super::super::{Token, TokenType};
+    use super::Compiler;
+
+    /// Build a `Token` without having to spell out `line` every time; every
+    /// source in these tests is a single line, so each token sits on line 1.
+    fn tok(tt: TokenType, start: usize, length: usize) -> Token {
+        Token {
+            tt,
+            line: 1,
+            start,
+            length,
+        }
+    }
+
+    #[test]
+    fn parses_an_entry_script_with_every_statement_kind() {
+        // "start script demo { jump foo call bar let x = 1 narrator : "hi" 2 + 2 }"
+        let source = r#"start script demo { jump foo call bar let x = 1 narrator : "hi" 2 + 2 }"#
+            .to_string();
+        let tokens = vec![
+            tok(TokenType::Start, 0, 5),
+            tok(TokenType::Script, 6, 6),
+            tok(TokenType::Identifier, 13, 4),
+            tok(TokenType::LeftBrace, 18, 1),
+            tok(TokenType::Jump, 20, 4),
+            tok(TokenType::Identifier, 25, 3),
+            tok(TokenType::NL, 28, 0),
+            tok(TokenType::Call, 29, 4),
+            tok(TokenType::Identifier, 34, 3),
+            tok(TokenType::NL, 37, 0),
+            tok(TokenType::Let, 38, 3),
+            tok(TokenType::Identifier, 42, 1),
+            tok(TokenType::Equal, 44, 1),
+            tok(TokenType::Number, 46, 1),
+            tok(TokenType::NL, 47, 0),
+            tok(TokenType::Identifier, 48, 8),
+            tok(TokenType::Colon, 57, 1),
+            tok(TokenType::String, 60, 2),
+            tok(TokenType::NL, 63, 0),
+            tok(TokenType::Number, 64, 1),
+            tok(TokenType::Plus, 66, 1),
+            tok(TokenType::Number, 68, 1),
+            tok(TokenType::RightBrace, 70, 1),
+            tok(TokenType::EOF, 71, 0),
+        ];
+
+        let mut compiler = Compiler::new(source, tokens);
+        let scripts = compiler.parse_scripts().expect("parsing failed");
+
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(
+            scripts[0],
+            ScriptDecl {
+                name: "demo".to_string(),
+                is_entry: true,
+                body: vec![
+                    Stmt::Jump("foo".to_string()),
+                    Stmt::Call("bar".to_string()),
+                    Stmt::Let(LetStmt {
+                        name: "x".to_string(),
+                        offset: 0,
+                    }),
+                    Stmt::Dialogue(DialogueStmt {
+                        speaker: Some("narrator".to_string()),
+                        text: "hi".to_string(),
+                    }),
+                    Stmt::PetrelBlock(2),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn script_decl_requires_the_script_keyword() {
+        let source = "start jump".to_string();
+        let tokens = vec![
+            tok(TokenType::Start, 0, 5),
+            tok(TokenType::Jump, 6, 4),
+            tok(TokenType::EOF, 10, 0),
+        ];
+
+        let mut compiler = Compiler::new(source, tokens);
+        assert!(compiler.parse_scripts().is_err());
+    }
+
+    #[test]
+    fn collects_every_error_instead_of_stopping_at_the_first() {
+        // Two malformed statements in one script body (`=` has no prefix
+        // rule to parse it as an expression): both should be recorded and
+        // recovered past, not just the first one.
+        let source = "start script demo { = = }".to_string();
+        let tokens = vec![
+            tok(TokenType::Start, 0, 5),
+            tok(TokenType::Script, 6, 6),
+            tok(TokenType::Identifier, 13, 4),
+            tok(TokenType::LeftBrace, 18, 1),
+            tok(TokenType::Equal, 20, 1),
+            tok(TokenType::NL, 21, 0),
+            tok(TokenType::Equal, 22, 1),
+            tok(TokenType::NL, 23, 0),
+            tok(TokenType::RightBrace, 24, 1),
+            tok(TokenType::EOF, 25, 0),
+        ];
+
+        let mut compiler = Compiler::new(source, tokens);
+        let errors = compiler
+            .parse_scripts()
+            .expect_err("both malformed statements should have been reported");
+        assert_eq!(errors.len(), 2);
     }
 }
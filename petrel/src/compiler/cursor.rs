@@ -0,0 +1,69 @@
+use super::{Token, TokenType};
+
+/// Synthesized in place of a real token whenever a lookup runs past the end
+/// of the stream: `previous()` before the first `advance()`, `current()` once
+/// the stream's real `EOF` token has itself been consumed, or any lookup in
+/// a hand-built stream with no trailing `EOF` — returning it instead of
+/// panicking keeps the cursor panic-free in every case.
+const PAST_EOF: Token = Token {
+    tt: TokenType::EOF,
+    line: 0,
+    start: 0,
+    length: 0,
+};
+
+/// Owns a parsed token stream and the `Compiler`'s position within it.
+/// Centralizes the index arithmetic the compiler used to do by hand, and
+/// adds `lookahead(n)`: statement parsing needs more than the one token of
+/// peeking `Compiler` used to have (e.g. telling a `speaker : "line"`
+/// dialogue from a bare `speaker` expression needs to see the `:` that
+/// follows the identifier).
+///
+/// Every lookup is panic-free: `current`, `previous`, and `lookahead` all
+/// return `EOF` rather than the out-of-bounds `.expect(...)` panics this
+/// replaces.
+#[derive(Debug)]
+pub(crate) struct TokenCursor {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl TokenCursor {
+    pub(crate) fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, index: 0 }
+    }
+
+    /// Consume the current token, moving the cursor one token ahead.
+    /// Saturates at the end of the stream instead of overshooting it.
+    pub(crate) fn advance(&mut self) {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+        }
+    }
+
+    /// The token at the cursor, without consuming it.
+    pub(crate) fn current(&self) -> &Token {
+        self.lookahead(0)
+    }
+
+    /// The most recently consumed token.
+    pub(crate) fn previous(&self) -> &Token {
+        self.index
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+            .unwrap_or(&PAST_EOF)
+    }
+
+    /// The `n`th token ahead of the cursor, without consuming anything.
+    /// `lookahead(0)` is the same token `current()` returns.
+    pub(crate) fn lookahead(&self, n: usize) -> &Token {
+        self.tokens.get(self.index + n).unwrap_or(&PAST_EOF)
+    }
+
+    /// The cursor's position in the stream, only for comparing "did we
+    /// actually consume anything" across a parse attempt — callers have no
+    /// other use for the raw index.
+    pub(crate) fn position(&self) -> usize {
+        self.index
+    }
+}
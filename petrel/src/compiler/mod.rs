@@ -1,6 +1,11 @@
+pub mod ast;
 #[allow(clippy::module_inception)]
 pub mod compiler;
+mod cursor;
 pub mod scanner;
+pub mod token;
 
+pub use ast::{DialogueStmt, LetStmt, ScriptDecl, Stmt};
 pub use compiler::Compiler;
 pub use scanner::Scanner;
+pub use token::{Token, TokenType};
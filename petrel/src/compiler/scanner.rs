@@ -0,0 +1,617 @@
+use crate::diagnostic::PetrelError;
+
+use super::{Token, TokenType};
+
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::io::Read;
+
+/// Scanner used to convert source text into a vector of [`Token`]s for the
+/// new-generation [`Compiler`](super::Compiler), filling in the lexer side
+/// of the token/grammar contract described in `grammar.ebnf`, producing this
+/// generation's `Token`/`TokenType`/`PetrelError`.
+pub struct Scanner {
+    /// The input for the scanner
+    pub source: Vec<char>,
+    /// The line number
+    line: usize,
+    /// The starting index
+    start: usize,
+}
+
+impl Scanner {
+    /// Create a new scanner from string
+    pub fn new(input: String) -> Scanner {
+        Scanner {
+            source: input.chars().collect(),
+            line: 1,
+            start: 0,
+        }
+    }
+
+    /// Read from file
+    #[cfg(feature = "std")]
+    pub fn from_file(path: &str) -> Result<Scanner, PetrelError> {
+        let mut file = File::open(path)?;
+        let mut input = String::new();
+        file.read_to_string(&mut input)?;
+
+        Ok(Scanner {
+            source: input.chars().collect(),
+            line: 1,
+            start: 0,
+        })
+    }
+
+    /// Check if the end of file token has been generated
+    fn end_of_file(tokens: &[Token]) -> bool {
+        matches!(
+            tokens.last(),
+            Some(Token {
+                tt: TokenType::EOF,
+                ..
+            })
+        )
+    }
+
+    /// Creates a token
+    #[inline]
+    fn make_token(&self, tt: TokenType, len: usize) -> Token {
+        Token {
+            tt,
+            line: self.line,
+            start: self.start,
+            length: len,
+        }
+    }
+
+    /// Creates a token that we already consumed
+    #[inline]
+    fn make_consumed_token(&self, tt: TokenType, len: usize) -> Token {
+        Token {
+            tt,
+            line: self.line,
+            start: self.start - len + 1,
+            length: len,
+        }
+    }
+
+    /// Move the start forward one, returning the next character
+    #[inline]
+    fn next(&mut self) -> Option<&char> {
+        self.start += 1;
+        self.source.get(self.start)
+    }
+
+    /// Advance the index by 1
+    #[inline]
+    fn advance(&mut self) {
+        self.start += 1;
+    }
+
+    /// Peek at next char without consuming the character
+    #[inline]
+    fn peek(&mut self) -> Option<&char> {
+        self.source.get(self.start + 1)
+    }
+
+    /// Get the current character
+    #[inline]
+    fn current(&self) -> Option<&char> {
+        self.source.get(self.start)
+    }
+
+    /// Create a string literal
+    fn string(&mut self) -> Result<Token, PetrelError> {
+        let mut s = self.peek();
+        let mut length = 0;
+        while let Some(c) = s {
+            if *c == '\\' {
+                length += 1;
+                self.advance();
+            } else if *c == '"' {
+                return Ok(self.make_consumed_token(TokenType::String, length));
+            }
+            length += 1;
+            self.advance();
+            s = self.peek();
+        }
+        Err(PetrelError::MissingDoubleQuote)
+    }
+
+    /// The text of a partially-scanned literal, for embedding in an error.
+    fn literal_so_far(&self, length: usize) -> String {
+        let start = self.start - length + 1;
+        self.source
+            .get(start..=self.start)
+            .expect("length never exceeds what's already been consumed")
+            .iter()
+            .collect()
+    }
+
+    /// Create a number literal.
+    ///
+    /// Accepts decimal integers and fractions (`12`, `12.5`), `0x`/`0b`/`0o`
+    /// prefixed hex/binary/octal integers, `_` as a digit-group separator
+    /// anywhere a digit is expected (`1_000_000`), and a decimal scientific
+    /// exponent (`1.5e-10`). A prefix with no digits after it, or a `_`
+    /// where a digit was required, is a [`PetrelError::MalformedNumber`].
+    fn number(&mut self) -> Result<Token, PetrelError> {
+        let mut length = 1;
+
+        // `0x`/`0b`/`0o` prefixed literal: a different digit class, and no
+        // fraction or exponent.
+        if *self.current().expect("number always starts on a digit") == '0' {
+            let base = match self.peek() {
+                Some('x') | Some('X') => Some(16),
+                Some('b') | Some('B') => Some(2),
+                Some('o') | Some('O') => Some(8),
+                _ => None,
+            };
+            if let Some(base) = base {
+                length += 1;
+                self.advance();
+                let digits_start = length;
+                let mut last_was_digit = false;
+                let mut s = self.peek();
+                while let Some(c) = s {
+                    if c.is_digit(base) {
+                        last_was_digit = true;
+                    } else if *c == '_' {
+                        last_was_digit = false;
+                    } else {
+                        break;
+                    }
+                    length += 1;
+                    self.advance();
+                    s = self.peek();
+                }
+                return if length == digits_start || !last_was_digit {
+                    Err(PetrelError::MalformedNumber(self.literal_so_far(length)))
+                } else {
+                    Ok(self.make_consumed_token(TokenType::Number, length))
+                };
+            }
+        }
+
+        // Decimal integer part.
+        let mut last_was_digit = true; // the leading digit dispatched us here
+        let mut s = self.peek();
+        while let Some(c) = s {
+            if c.is_ascii_digit() {
+                last_was_digit = true;
+            } else if *c == '_' {
+                last_was_digit = false;
+            } else {
+                break;
+            }
+            length += 1;
+            self.advance();
+            s = self.peek();
+        }
+        if !last_was_digit {
+            return Err(PetrelError::MalformedNumber(self.literal_so_far(length)));
+        }
+
+        // Fractional part. Unlike the other digit runs, an empty fraction
+        // (a bare trailing `.`) isn't itself malformed - it just ends the
+        // number there, as it always has.
+        if let Some('.') = s {
+            length += 1;
+            self.advance();
+            let mut trailing_underscore = false;
+            s = self.peek();
+            while let Some(c) = s {
+                if c.is_ascii_digit() {
+                    trailing_underscore = false;
+                } else if *c == '_' {
+                    trailing_underscore = true;
+                } else {
+                    break;
+                }
+                length += 1;
+                self.advance();
+                s = self.peek();
+            }
+            if trailing_underscore {
+                return Err(PetrelError::MalformedNumber(self.literal_so_far(length)));
+            }
+        }
+
+        // Exponent: `e`/`E`, an optional sign, then one or more digits.
+        if matches!(s, Some('e') | Some('E')) {
+            length += 1;
+            self.advance();
+            s = self.peek();
+            if matches!(s, Some('+') | Some('-')) {
+                length += 1;
+                self.advance();
+                s = self.peek();
+            }
+            last_was_digit = false;
+            while let Some(c) = s {
+                if c.is_ascii_digit() {
+                    last_was_digit = true;
+                } else if *c == '_' {
+                    last_was_digit = false;
+                } else {
+                    break;
+                }
+                length += 1;
+                self.advance();
+                s = self.peek();
+            }
+            if !last_was_digit {
+                return Err(PetrelError::MalformedNumber(self.literal_so_far(length)));
+            }
+        }
+
+        Ok(self.make_consumed_token(TokenType::Number, length))
+    }
+
+    /// Skip through a comment
+    fn comment(&mut self) {
+        while let Some(c) = self.next() {
+            if *c == '\n' {
+                self.line += 1;
+                break;
+            }
+        }
+        self.advance();
+    }
+
+    /// Create an identifier with a given prefix
+    fn identifier(&mut self, prefix: &str) -> Token {
+        let mut len = prefix.len();
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || *c == '_' {
+                len += 1;
+                self.advance()
+            } else {
+                break;
+            }
+        }
+        self.make_consumed_token(TokenType::Identifier, len)
+    }
+
+    /// Check for keywords or create an identifier
+    fn keyword(&mut self) -> Token {
+        if let Some(c) = self.current() {
+            use TokenType::*;
+            match c {
+                'e' => self.check_word("else", 1, Else),
+                'j' => self.check_word("jump", 1, Jump),
+                'l' => self.check_word("let", 1, Let),
+                'n' => self.check_word("null", 1, Null),
+                'r' => self.check_word("return", 1, Return),
+                'u' => self.check_word("use", 1, Use),
+                'v' => self.check_word("var", 1, Var),
+                'w' => self.check_word("while", 1, While),
+
+                // Ambiguous keywords, disambiguated by looking one (or
+                // more) characters ahead before committing to a `check_word`.
+                'c' => match self.next() {
+                    Some('o') => self.check_word("const", 2, Const),
+                    Some('a') => self.check_word("call", 2, Call),
+                    _ => self.identifier("c"),
+                },
+                'f' => match self.next() {
+                    Some('a') => self.check_word("false", 2, False),
+                    Some('o') => self.check_word("for", 2, For),
+                    Some('r') => self.check_word("from", 2, From),
+                    Some('u') => self.check_word("fun", 2, Fun),
+                    _ => self.identifier("f"),
+                },
+                'i' => match self.next() {
+                    Some('f') => self.check_word("if", 2, If),
+                    Some('m') => self.check_word("impl", 2, Impl),
+                    Some('n') => self.check_word("in", 2, In),
+                    _ => self.identifier("i"),
+                },
+                's' => match self.next() {
+                    Some('c') => self.check_word("script", 2, Script),
+                    Some('u') => self.check_word("super", 2, Super),
+                    Some('t') => match self.next() {
+                        Some('a') => self.check_word("start", 3, Start),
+                        Some('r') => self.check_word("struct", 3, Struct),
+                        _ => self.identifier("st"),
+                    },
+                    _ => self.identifier("s"),
+                },
+                't' => match self.next() {
+                    Some('h') => self.check_word("this", 2, This),
+                    Some('r') => match self.next() {
+                        Some('a') => self.check_word("trait", 3, Trait),
+                        Some('u') => self.check_word("true", 3, True),
+                        _ => self.identifier("tr"),
+                    },
+                    _ => self.identifier("t"),
+                },
+                _ => self.identifier(&c.to_string()),
+            }
+        } else {
+            self.make_token(TokenType::EOF, 0)
+        }
+    }
+
+    /// Check if a keyword matches up
+    fn check_word(&mut self, to_check: &str, mut length: usize, keyword: TokenType) -> Token {
+        for c in to_check.chars().skip(length) {
+            if let Some(l) = self.peek() {
+                if c != *l {
+                    return self.identifier(to_check.get(0..length).expect("Unreachable"));
+                } else {
+                    length += 1;
+                    self.advance();
+                }
+            } else {
+                return self.identifier(to_check.get(0..length).expect("Unreachable"));
+            }
+        }
+        if let Some(l) = self.peek() {
+            if l.is_alphanumeric() || *l == '_' {
+                // Trailing identifier characters, so this is a longer identifier.
+                self.identifier(to_check)
+            } else {
+                self.make_consumed_token(keyword, length)
+            }
+        } else {
+            self.make_consumed_token(keyword, length)
+        }
+    }
+
+    /// Scan the input into the tokens
+    pub fn scan(&mut self) -> Result<Vec<Token>, PetrelError> {
+        let mut tokens: Vec<Token> = vec![];
+
+        while !Self::end_of_file(&tokens) {
+            let t = self.scan_token()?;
+            tokens.push(t);
+            self.advance();
+        }
+
+        Ok(tokens)
+    }
+
+    /// Scan a singular token: the arithmetic, comparison, grouping, and
+    /// literal tokens the Pratt parser and the statement parser both
+    /// expect, per `grammar.ebnf`.
+    pub fn scan_token(&mut self) -> Result<Token, PetrelError> {
+        if let Some(c) = self.current() {
+            use TokenType::*;
+            match c {
+                // Single-character tokens
+                '.' => Ok(self.make_token(Dot, 1)),
+                '?' => Ok(self.make_token(QuestionMark, 1)),
+                '+' => Ok(self.make_token(Plus, 1)),
+                '/' => Ok(self.make_token(Slash, 1)),
+                '*' => Ok(self.make_token(Star, 1)),
+                ',' => Ok(self.make_token(Comma, 1)),
+
+                // Brackets
+                '(' => Ok(self.make_token(LeftParen, 1)),
+                ')' => Ok(self.make_token(RightParen, 1)),
+                '{' => Ok(self.make_token(LeftBrace, 1)),
+                '}' => Ok(self.make_token(RightBrace, 1)),
+                '[' => Ok(self.make_token(LeftBracket, 1)),
+                ']' => Ok(self.make_token(RightBracket, 1)),
+
+                // One-char lookahead for multi-char operators; a match
+                // consumes the second char so it isn't scanned again.
+                '!' => match self.peek() {
+                    Some('=') => { self.advance(); Ok(self.make_consumed_token(BangEqual, 2)) }
+                    _ => Ok(self.make_token(Bang, 1)),
+                },
+                '-' => match self.peek() {
+                    Some('>') => { self.advance(); Ok(self.make_consumed_token(Arrow, 2)) }
+                    _ => Ok(self.make_token(Minus, 1)),
+                },
+                '<' => match self.peek() {
+                    Some('=') => { self.advance(); Ok(self.make_consumed_token(LessEqual, 2)) }
+                    _ => Ok(self.make_token(Less, 1)),
+                },
+                '>' => match self.peek() {
+                    Some('=') => { self.advance(); Ok(self.make_consumed_token(GreaterEqual, 2)) }
+                    _ => Ok(self.make_token(Greater, 1)),
+                },
+                ':' => match self.peek() {
+                    Some(':') => { self.advance(); Ok(self.make_consumed_token(DoubleColon, 2)) }
+                    _ => Ok(self.make_token(Colon, 1)),
+                },
+                '=' => match self.peek() {
+                    Some('=') => { self.advance(); Ok(self.make_consumed_token(DoubleEqual, 2)) }
+                    _ => Ok(self.make_token(Equal, 1)),
+                },
+                '&' => match self.peek() {
+                    Some('&') => { self.advance(); Ok(self.make_consumed_token(And, 2)) }
+                    _ => Err(PetrelError::UnknownCharacter('&')),
+                },
+                '|' => match self.peek() {
+                    Some('|') => { self.advance(); Ok(self.make_consumed_token(Or, 2)) }
+                    _ => Err(PetrelError::UnknownCharacter('|')),
+                },
+
+                // Special
+                '"' => {
+                    let tk = self.string();
+                    // Go past remaining quote
+                    self.advance();
+                    tk
+                }
+                '#' => {
+                    self.comment();
+                    self.scan_token()
+                }
+                '\n' => {
+                    self.line += 1;
+                    Ok(self.make_token(NL, 1))
+                }
+
+                _ => {
+                    if c.is_ascii_digit() {
+                        self.number()
+                    } else if c.is_alphabetic() || *c == '_' {
+                        Ok(self.keyword())
+                    } else if c.is_whitespace() {
+                        self.advance();
+                        self.scan_token()
+                    } else {
+                        Err(PetrelError::UnknownCharacter(*c))
+                    }
+                }
+            }
+        } else {
+            Ok(self.make_token(TokenType::EOF, 0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod scanner_test {
+    use super::*;
+
+    fn scan(source: &str) -> Vec<TokenType> {
+        Scanner::new(source.to_string())
+            .scan()
+            .expect("scanning failed")
+            .into_iter()
+            .map(|t| t.tt)
+            .collect()
+    }
+
+    fn contained_strings(source: &str) -> Vec<String> {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner
+            .scan()
+            .expect("scanning failed")
+            .into_iter()
+            .map(|t| {
+                scanner
+                    .source
+                    .get(t.start..(t.start + t.length))
+                    .expect("token out of range")
+                    .iter()
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arithmetic_comparison_and_grouping_tokens() {
+        use TokenType::*;
+        assert_eq!(
+            scan("(1 + 2) * 3 - 4 / 5 == 6 != 7 <= 8 >= 9 < 1 > 1 !1"),
+            vec![
+                LeftParen,
+                Number,
+                Plus,
+                Number,
+                RightParen,
+                Star,
+                Number,
+                Minus,
+                Number,
+                Slash,
+                Number,
+                DoubleEqual,
+                Number,
+                BangEqual,
+                Number,
+                LessEqual,
+                Number,
+                GreaterEqual,
+                Number,
+                Less,
+                Number,
+                Greater,
+                Number,
+                Bang,
+                Number,
+                EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn literal_keywords() {
+        use TokenType::*;
+        assert_eq!(scan("true false null"), vec![True, False, Null, EOF]);
+    }
+
+    #[test]
+    fn story_script_keywords() {
+        use TokenType::*;
+        assert_eq!(
+            scan("start script demo { jump foo call bar let x = 1 }"),
+            vec![
+                Start, Script, Identifier, LeftBrace, Jump, Identifier, Call, Identifier, Let,
+                Identifier, Equal, Number, RightBrace, EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn keyword_prefixed_identifiers_stay_identifiers() {
+        use TokenType::*;
+        assert_eq!(scan("forest"), vec![Identifier, EOF]);
+        assert_eq!(contained_strings("forest"), vec!["forest".to_string()]);
+    }
+
+    #[test]
+    fn number_with_fraction() {
+        assert_eq!(
+            contained_strings("12.5"),
+            vec!["12.5".to_string(), "".to_string()]
+        );
+    }
+
+    /// Hex, binary, and octal literals, plus `_` digit-group separators.
+    #[test]
+    fn extended_integer_literals() {
+        assert_eq!(
+            contained_strings("0x1F 0b1010 0o17 1_000_000"),
+            vec![
+                "0x1F".to_string(),
+                "0b1010".to_string(),
+                "0o17".to_string(),
+                "1_000_000".to_string(),
+                "".to_string(),
+            ]
+        );
+    }
+
+    /// Scientific notation with a signed exponent.
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(
+            contained_strings("1.5e-10 2E3"),
+            vec!["1.5e-10".to_string(), "2E3".to_string(), "".to_string()]
+        );
+    }
+
+    /// A prefix with no digits, or a trailing separator, is malformed.
+    #[test]
+    fn malformed_number_literals() {
+        assert!(matches!(
+            Scanner::new("0x".to_string()).scan(),
+            Err(PetrelError::MalformedNumber(_))
+        ));
+        assert!(matches!(
+            Scanner::new("1_".to_string()).scan(),
+            Err(PetrelError::MalformedNumber(_))
+        ));
+        assert!(matches!(
+            Scanner::new("1e".to_string()).scan(),
+            Err(PetrelError::MalformedNumber(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_bare_ampersand_is_an_error() {
+        assert!(matches!(
+            Scanner::new("&".to_string()).scan(),
+            Err(PetrelError::UnknownCharacter('&'))
+        ));
+    }
+}
@@ -37,6 +37,8 @@ pub enum TokenType {
     DoubleEqual,
     DoubleColon,
     BangEqual,
+    And,
+    Or,
 
     // Keywords
     Const,
@@ -59,6 +61,13 @@ pub enum TokenType {
     Var,
     While,
 
+    // Story-script keywords (see `grammar.ebnf`)
+    Start,
+    Script,
+    Jump,
+    Call,
+    Let,
+
     // Literals
     Identifier,
     String,
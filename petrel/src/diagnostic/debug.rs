@@ -1,38 +1,98 @@
 use crate::runtime::vm::{Opcode, VM};
 
-pub fn dissasemble_instruction(vm: &VM, offset: usize) {
-    print!("{:04} ", offset);
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use core::fmt::Write;
+
+/// Format a single instruction at `offset`, the same line `println!` would
+/// have printed under `std`. Kept separate so `std` builds can still print
+/// straight away while `no_std` builds get a `String` to route through
+/// whatever sink they have (UART, log buffer, ...).
+pub(crate) fn format_instruction(vm: &VM, offset: usize) -> String {
+    let mut out = String::new();
+    let _ = write!(out, "{:04} ", offset);
     if offset > 0 && vm.instructions[offset].line == vm.instructions[offset - 1].line {
-        print!("{:>3}", '|')
+        let _ = write!(out, "{:>3}", '|');
     } else {
-        print!("{:3>}", vm.instructions[offset].line)
+        let _ = write!(out, "{:3>}", vm.instructions[offset].line);
     }
 
     let instruction = vm.instructions[offset].opcode;
-    use crate::runtime::vm::Opcode::*;
     match Opcode::try_from(instruction) {
-        Ok(OpReturn) => println!(" Return"),
-        Ok(OpConstant) => println!(
-            " Constant {}: {}",
-            vm.instructions[offset + 1].opcode,
-            vm.constants[vm.instructions[offset + 1].opcode as usize]
-        ),
-        Ok(OpNegate) => println!(" Negate"),
-        Ok(OpAdd) => println!(" Add"),
-        Ok(OpSubtract) => println!(" Subtract"),
-        Ok(OpMultiply) => println!(" Multiply"),
-        Ok(OpDivide) => println!(" Divide"),
-        Ok(OpTrue) => println!(" True"),
-        Ok(OpFalse) => println!(" False"),
-        Ok(OpNull) => println!(" Null"),
-        Ok(OpNot) => println!(" Not"),
+        Ok(Opcode::OpConstant) => {
+            let _ = write!(
+                out,
+                " Constant {}: {}",
+                vm.instructions[offset + 1].opcode,
+                vm.constants[vm.instructions[offset + 1].opcode as usize]
+            );
+        }
+        Ok(Opcode::OpConstantLong) => {
+            let hi = vm.instructions[offset + 1].opcode as usize;
+            let mid = vm.instructions[offset + 2].opcode as usize;
+            let lo = vm.instructions[offset + 3].opcode as usize;
+            let index = (hi << 16) | (mid << 8) | lo;
+            let _ = write!(out, " ConstantLong {}: {}", index, vm.constants[index]);
+        }
+        Ok(Opcode::OpCall) => {
+            let index = vm.instructions[offset + 1].opcode;
+            let _ = write!(
+                out,
+                " Call {}: {}",
+                index,
+                vm.native_name(index).unwrap_or("<unknown>")
+            );
+        }
+        Ok(Opcode::OpInvoke) => {
+            let argc = vm.instructions[offset + 1].opcode;
+            let _ = write!(out, " Invoke ({argc} args)");
+        }
+        Ok(op @ (Opcode::OpJump | Opcode::OpJumpIfFalse | Opcode::OpLoop)) => {
+            let hi = vm.instructions[offset + 1].opcode;
+            let lo = vm.instructions[offset + 2].opcode;
+            let jump_offset = ((hi as u16) << 8) | lo as u16;
+            let target = if matches!(op, Opcode::OpLoop) {
+                offset as isize - jump_offset as isize
+            } else {
+                offset as isize + 3 + jump_offset as isize
+            };
+            let _ = write!(out, " {} {} -> {}", op.mnemonic(), offset, target);
+        }
+        Ok(op) => {
+            let _ = write!(out, " {}", op.mnemonic());
+        }
         Err(e) => panic!("{}", e),
     }
+    out
+}
+
+#[cfg(feature = "std")]
+pub fn dissasemble_instruction(vm: &VM, offset: usize) {
+    println!("{}", format_instruction(vm, offset));
 }
 
+/// `no_std` counterpart of [`dissasemble_instruction`]: same formatting, but
+/// written into the caller's sink instead of stdout.
+#[cfg(not(feature = "std"))]
+pub fn dissasemble_instruction(vm: &VM, offset: usize, out: &mut dyn Write) {
+    let _ = writeln!(out, "{}", format_instruction(vm, offset));
+}
+
+#[cfg(feature = "std")]
 pub fn dissasemble_vm(vm: &VM, name: &str) {
     println!("===={}====", name);
     for i in 0..vm.instructions.len() {
         dissasemble_instruction(vm, i);
     }
 }
+
+#[cfg(not(feature = "std"))]
+pub fn dissasemble_vm(vm: &VM, name: &str, out: &mut dyn Write) {
+    let _ = writeln!(out, "===={}====", name);
+    for i in 0..vm.instructions.len() {
+        dissasemble_instruction(vm, i, out);
+    }
+}
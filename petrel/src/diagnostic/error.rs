@@ -1,6 +1,16 @@
+#[cfg(not(feature = "std"))]
+use core::fmt::{Debug, Display};
+#[cfg(feature = "std")]
 use std::fmt::{Debug, Display};
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
 
 use crate::compiler::token::Token;
+#[cfg(feature = "std")]
 use colored::*;
 use thiserror::Error;
 
@@ -9,18 +19,31 @@ use crate::runtime::vm::Opcode;
 /// General error types
 #[derive(Error, Debug)]
 pub enum PetrelError {
+    // `std::io::Error` has no `core`/`alloc` equivalent, so this variant
+    // (and the `#[from]` conversion it brings with it) only exists when
+    // `std` is available.
+    #[cfg(feature = "std")]
     #[error("file {0} not found")]
     FileNotFound(#[from] std::io::Error),
     #[error("unknown character {0}")]
     UnknownCharacter(char),
     #[error("missing double quote (\")")]
     MissingDoubleQuote,
+    #[error("malformed number literal: {0}")]
+    MalformedNumber(String),
     #[error("virtual machine ran into problem {0}")]
     VMError(#[from] VMError),
     #[error("tried to use token {0} in array of length {1}")]
     TokenOutOfBounds(usize, usize),
     #[error("{}{0}", "Syntax error\n".blue().bold())]
     SyntaxError(#[from] SyntaxError),
+    /// Marks that a syntax error was already pushed into
+    /// [`Compiler::errors`](crate::compiler::Compiler) and the parser has
+    /// synchronized past it to keep going. Purely a `?`-propagation signal
+    /// for unwinding out of the abandoned statement/expression — never
+    /// meant to be shown to a user on its own.
+    #[error("(error already reported)")]
+    Recovering,
 }
 
 /// Errors that can occur in the VM
@@ -34,6 +57,46 @@ pub enum VMError {
     EmptyStack,
     #[error("encountered end of instructions with no return")]
     NoReturn,
+    #[error("attempted to divide by zero")]
+    DivideByZero,
+    #[error("invalid memory access at {addr:#x}")]
+    MemoryFault { addr: usize },
+    #[error("{0}")]
+    Runtime(Context),
+    #[error("stack overflow")]
+    StackOverflow,
+    #[error("no native function registered at index {0}")]
+    UnknownNative(usize),
+    #[error("malformed bytecode: {0}")]
+    BadBytecode(&'static str),
+    #[error("bytecode was compiled with format version {found}, this VM reads version {expected}")]
+    VersionMismatch { expected: u8, found: u8 },
+    #[error("native call failed: {0}")]
+    NativeCallFailed(String),
+}
+
+/// Where a runtime error happened, printed as "message (source: S, line N)".
+#[derive(Debug)]
+pub struct Context {
+    source: String,
+    line: usize,
+    info: String,
+}
+
+impl Context {
+    pub fn new(source: String, line: usize, info: String) -> Self {
+        Self { source, line, info }
+    }
+}
+
+impl Display for Context {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} (source: {}, line {})",
+            self.info, self.source, self.line
+        )
+    }
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -61,7 +124,7 @@ pub struct Annotation {
 }
 
 impl Display for Annotation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.print_error())
     }
 }
@@ -76,7 +139,12 @@ impl Annotation {
     }
 
     pub fn print_error(&self) -> String {
+        // Coloring goes through the `colored` crate, which needs `std` to
+        // detect a terminal; `no_std` builds get the same text uncolored.
+        #[cfg(feature = "std")]
         let l1 = format!("{}{}\n", "error: ".red().bold(), self.info.bold());
+        #[cfg(not(feature = "std"))]
+        let l1 = format!("error: {}\n", self.info);
         let l2 = format!(" from --> {:-<50}\n", self.source);
         let l3 = format!(" {:<3} | {}\n", self.token.line, self.source);
         let l4 = format!(
@@ -0,0 +1,162 @@
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use crate::compiler::token::TokenType;
+use crate::compiler::{Compiler, Scanner};
+use crate::diagnostic::PetrelError;
+use crate::runtime::vm::VM;
+
+/// Interactive front-end for `AVN`: reads Petrel expressions, compiles them
+/// onto a persistent [`VM`], and runs each one as it's entered so the
+/// `stack`/`constants` built up by earlier entries are still there for the
+/// next one, the same way a plain `python`/`node` shell works.
+pub struct Repl {
+    vm: VM,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self { vm: VM::new() }
+    }
+
+    /// Run the prompt loop until the user sends EOF (Ctrl-D) or interrupts
+    /// with Ctrl-C.
+    pub fn run(&mut self) -> rustyline::Result<()> {
+        let mut editor = DefaultEditor::new()?;
+
+        loop {
+            match self.read_entry(&mut editor)? {
+                Some(source) => self.eval(source),
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read one logical entry, prompting again with a continuation marker
+    /// while brackets or a string literal are left unbalanced. Returns
+    /// `Ok(None)` once the user signals they're done (Ctrl-D/Ctrl-C).
+    fn read_entry(&self, editor: &mut DefaultEditor) -> rustyline::Result<Option<String>> {
+        let mut buffer = String::new();
+
+        loop {
+            let prompt = if buffer.is_empty() { "> " } else { ".. " };
+            let line = match editor.readline(prompt) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+
+            if !buffer.is_empty() {
+                buffer.push('\n');
+            }
+            buffer.push_str(&line);
+
+            match Scanner::new(buffer.clone()).scan() {
+                // An unterminated string literal just means "keep reading";
+                // anything else is a real scan error to surface now.
+                Err(PetrelError::MissingDoubleQuote) => continue,
+                Err(e) => {
+                    println!("{}", e);
+                    buffer.clear();
+                    continue;
+                }
+                Ok(tokens) => {
+                    editor.add_history_entry(&buffer).ok();
+                    if needs_more_input(&tokens) {
+                        continue;
+                    }
+                    return Ok(Some(buffer));
+                }
+            }
+        }
+    }
+
+    /// Compile `source` onto the persistent VM and run it, printing the
+    /// value left on top of the stack. A compile error, whether from
+    /// scanning or from `Compiler::compile`'s synchronized error batch, is
+    /// reported through `PetrelError`'s (already-colored) `Display` impl
+    /// rather than aborting the session.
+    fn eval(&mut self, source: String) {
+        let tokens = match Scanner::new(source.clone()).scan() {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+
+        let mut compiler = Compiler::new(source, tokens);
+        compiler.vm = std::mem::replace(&mut self.vm, VM::new());
+        // `compile()` appends this entry's instructions after whatever's
+        // already there; `run()` needs to start from there too, not from
+        // wherever `ip` was left pointing by the previous entry's `OpReturn`.
+        let entry_start = compiler.vm.instructions.len();
+
+        let had_errors = match compiler.compile() {
+            Ok(_) => false,
+            Err(errors) => {
+                for e in errors {
+                    println!("{}", e);
+                }
+                true
+            }
+        };
+
+        self.vm = compiler.vm;
+        if had_errors {
+            return;
+        }
+        self.vm.ip = entry_start;
+
+        match self.vm.run(false) {
+            Ok(()) => {
+                if let Some(value) = self.vm.stack.last() {
+                    println!("{}", value);
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Unbalanced `{`/`}` means the entry is still open, the same signal the
+/// scanner surfaces for `fun foo() {` spanning multiple lines.
+fn needs_more_input(tokens: &[crate::compiler::token::Token]) -> bool {
+    let mut depth: i32 = 0;
+    for token in tokens {
+        match token.tt {
+            TokenType::LeftBrace => depth += 1,
+            TokenType::RightBrace => depth -= 1,
+            _ => {}
+        }
+    }
+    depth > 0
+}
+
+#[cfg(test)]
+mod repl_test {
+    use super::Repl;
+    use crate::common::value::Value;
+
+    /// A second `eval()` entry has to actually run: it used to just
+    /// re-execute the first entry's already-returned `OpReturn` and leave
+    /// the first entry's result sitting on the stack forever.
+    #[test]
+    fn second_entry_runs_its_own_instructions() {
+        let mut repl = Repl::new();
+
+        repl.eval("1 + 1".to_string());
+        assert_eq!(repl.vm.stack.last(), Some(&Value::Number(2.0)));
+
+        repl.eval("2 + 2".to_string());
+        assert_eq!(repl.vm.stack.last(), Some(&Value::Number(4.0)));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::diagnostic::BlockError;
-use std::ptr::NonNull;
+use core::ptr::NonNull;
 
 pub type BlockPtr = NonNull<u8>;
 pub type BlockSize = usize;
@@ -36,10 +36,15 @@ mod internal {
     use crate::diagnostic::BlockError;
 
     use super::{BlockPtr, BlockSize};
-    use std::{
-        alloc::{alloc, dealloc, Layout},
-        ptr::NonNull,
-    };
+    use core::{alloc::Layout, ptr::NonNull};
+
+    // `alloc`/`dealloc` themselves come from `std::alloc` when it's available
+    // and from the `alloc` crate's own global allocator otherwise, so a block
+    // can be carved out the same way on a freestanding target as on a host OS.
+    #[cfg(feature = "std")]
+    use std::alloc::{alloc, dealloc};
+    #[cfg(not(feature = "std"))]
+    use alloc::alloc::{alloc, dealloc};
 
     pub fn alloc_block(size: BlockSize) -> Result<BlockPtr, BlockError> {
         unsafe {
@@ -1,5 +1,12 @@
 use super::block::Block;
 
+#[cfg(feature = "std")]
+use std::boxed::Box;
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
 pub const BLOCK_SIZE_BITS: usize = 15;
 pub const BLOCK_SIZE: usize = 1 << BLOCK_SIZE_BITS;
 pub const LINE_SIZE_BITS: usize = 7;
@@ -12,6 +19,47 @@ pub struct BlockMeta {
 }
 
 impl BlockMeta {
+    pub fn new() -> Self {
+        Self {
+            line_mark: [false; LINE_COUNT],
+            block_mark: false,
+        }
+    }
+
+    /// Mark the line at `index` live. Called by the collector's mark phase
+    /// for every line an object touches.
+    pub fn mark_line(&mut self, index: usize) {
+        self.line_mark[index] = true;
+    }
+
+    /// Mark the whole block live. Called once a block is known to hold at
+    /// least one live object.
+    pub fn mark_block(&mut self) {
+        self.block_mark = true;
+    }
+
+    pub fn is_block_marked(&self) -> bool {
+        self.block_mark
+    }
+
+    /// How many of this block's lines are marked live.
+    pub fn marked_line_count(&self) -> usize {
+        self.line_mark.iter().filter(|marked| **marked).count()
+    }
+
+    /// A block is "fragmented" once it's live but only sparsely occupied,
+    /// making it a good evacuation candidate rather than just a recycle one.
+    pub fn is_fragmented(&self, threshold: f32) -> bool {
+        self.block_mark && (self.marked_line_count() as f32 / LINE_COUNT as f32) < threshold
+    }
+
+    /// Clear all marks ahead of the next mark phase, or before returning a
+    /// fully-reclaimed block to the free list.
+    pub fn reset(&mut self) {
+        self.line_mark = [false; LINE_COUNT];
+        self.block_mark = false;
+    }
+
     /// Find the next hole in the block from starting_at. Returns the cursor location in the block
     /// and the limit of its size
     ///
@@ -73,6 +121,12 @@ impl BlockMeta {
     }
 }
 
+impl Default for BlockMeta {
+    fn default() -> Self {
+        BlockMeta::new()
+    }
+}
+
 pub struct BumpBlock {
     cursor: usize,
     limit: usize,
@@ -81,16 +135,58 @@ pub struct BumpBlock {
 }
 
 impl BumpBlock {
-    /// Return pointer to available space in the block that can hold an object of alloc_size
+    /// Wrap a fresh `Block` as one big hole spanning the whole block.
+    pub fn new(block: Block) -> Self {
+        BumpBlock {
+            cursor: 0,
+            limit: BLOCK_SIZE,
+            block,
+            meta: Box::new(BlockMeta::new()),
+        }
+    }
+
+    /// Return pointer to available space in the block that can hold an object of alloc_size.
+    ///
+    /// Bumps within the current hole (`[cursor, limit)`); once an allocation
+    /// would cross the hole's end, `find_next_available_hole` looks for
+    /// another one starting from the hole's end instead of assuming the rest of
+    /// the block (up to `BLOCK_SIZE`) is free, since a recycled block can
+    /// have live lines mixed in past the current hole.
     pub fn inner_alloc(&mut self, alloc_size: usize) -> Option<*const u8> {
         let next_bump = self.cursor + alloc_size;
 
-        if next_bump > BLOCK_SIZE {
-            None
-        } else {
-            let offset = self.cursor;
-            self.cursor = next_bump;
-            unsafe { Some(self.block.as_ptr().add(offset) as *const u8) }
+        if next_bump > self.limit {
+            let (cursor, limit) = self.meta.find_next_available_hole(self.limit)?;
+            if cursor + alloc_size > limit {
+                return None;
+            }
+            self.cursor = cursor;
+            self.limit = limit;
+            return self.inner_alloc(alloc_size);
         }
+
+        let offset = self.cursor;
+        self.cursor = next_bump;
+        unsafe { Some(self.block.as_ptr().add(offset) as *const u8) }
+    }
+
+    pub fn meta(&self) -> &BlockMeta {
+        &self.meta
+    }
+
+    pub fn meta_mut(&mut self) -> &mut BlockMeta {
+        &mut self.meta
+    }
+
+    /// Byte offset of `ptr` from the start of this block, used by the
+    /// collector to turn an object pointer into a line index to mark.
+    pub fn offset_of(&self, ptr: *const u8) -> usize {
+        ptr as usize - self.block.as_ptr() as usize
+    }
+
+    /// Reclaim this block's underlying memory, discarding its mark metadata.
+    /// Used once the sweep phase finds a block with nothing live in it.
+    pub fn into_block(self) -> Block {
+        self.block
     }
 }
@@ -0,0 +1,47 @@
+use crate::common::value::Value;
+
+/// A recoverable fault raised by the VM while executing an instruction.
+///
+/// Unlike a `PetrelError`, a `Trap` isn't necessarily fatal: it's handed to
+/// the installed [`TrapHandler`] (if any) before the VM decides whether to
+/// abort, carry on, or substitute a value and keep going.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trap {
+    /// `OpDivide` was asked to divide by zero.
+    DivideByZero,
+    /// A pop was attempted on an empty stack.
+    StackUnderflow,
+    /// `instructions[ip].opcode` didn't match any known `Opcode`.
+    InvalidOpcode(u8),
+    /// A memory access (e.g. through the GC) referenced an invalid address.
+    MemoryFault { addr: usize },
+    /// `OpCall`/`OpInvoke` referenced a native index with nothing registered
+    /// at it. Carries the full `usize` index rather than narrowing to `u8`:
+    /// `OpInvoke`'s `Value::Native` index is deserialized from a `u64` and
+    /// isn't bounded to a byte the way `OpCall`'s inline operand is.
+    UnknownNative(usize),
+}
+
+/// What the VM should do after a [`Trap`] has been handled.
+pub enum TrapAction {
+    /// Give up and surface the trap as a `PetrelError`.
+    Abort,
+    /// Drop the faulting operation and keep executing.
+    Resume,
+    /// Push `Value` in place of the faulting operation's result and keep executing.
+    Supply(Value),
+}
+
+/// A read-only snapshot of VM state passed to a [`TrapHandler`] so it can
+/// make a decision without being able to mutate the VM out from under it.
+pub struct VMState<'a> {
+    pub ip: usize,
+    pub stack: &'a [Value],
+    pub constants: &'a [Value],
+}
+
+/// Installed on a [`VM`](crate::runtime::vm::VM) to implement a custom fault
+/// policy (logging, recovery, sandboxing, ...) instead of a hard crash.
+pub trait TrapHandler {
+    fn handle(&mut self, vm: &VMState, trap: Trap) -> TrapAction;
+}
@@ -1,44 +1,23 @@
+use core::fmt::Write as _;
+
 use crate::common::value::Value;
+#[cfg(feature = "std")]
 use crate::diagnostic::debug::dissasemble_instruction;
+use crate::diagnostic::debug::format_instruction;
 use crate::diagnostic::{Context, PetrelError, VMError};
+use crate::runtime::trap::{Trap, TrapAction, TrapHandler, VMState};
 
-#[derive(Debug)]
-#[repr(u8)]
-pub enum Opcode {
-    OpReturn,
-    OpConstant,
-    OpNegate,
-    OpAdd,
-    OpSubtract,
-    OpMultiply,
-    OpDivide,
-    OpNull,
-    OpTrue,
-    OpFalse,
-    OpNot,
-}
+#[cfg(feature = "std")]
+use std::{boxed::Box, string::String, vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
 
-impl TryFrom<u8> for Opcode {
-    type Error = VMError;
-    fn try_from(src: u8) -> Result<Self, Self::Error> {
-        match src {
-            0 => Ok(Opcode::OpReturn),
-            1 => Ok(Opcode::OpConstant),
-            2 => Ok(Opcode::OpNegate),
-            3 => Ok(Opcode::OpAdd),
-            4 => Ok(Opcode::OpSubtract),
-            5 => Ok(Opcode::OpMultiply),
-            6 => Ok(Opcode::OpDivide),
-            _ => Err(VMError::InvalidOpcodeConversion(src)),
-        }
-    }
-}
-
-impl From<Opcode> for u8 {
-    fn from(code: Opcode) -> Self {
-        code as u8
-    }
-}
+// `Opcode`, its `TryFrom<u8>`/`From<Opcode>` conversions, `Opcode::operand_len`,
+// and `Opcode::mnemonic` are generated by `build.rs` from `instructions.in` so
+// the instruction set has a single source of truth instead of drifting copies.
+include!(concat!(env!("OUT_DIR"), "/opcode_gen.rs"));
 
 #[derive(Debug)]
 pub struct Operation {
@@ -46,17 +25,87 @@ pub struct Operation {
     pub line: usize,
 }
 
-#[derive(Debug)]
+/// Default capacity of the value stack. Override with [`VM::with_stack_size`].
+pub const STACK_SIZE: usize = 256;
+
+/// A host function reachable from Petrel code via `OpCall`. Given mutable
+/// access to the operand stack, it pops its own arguments off the top and
+/// pushes its result(s), the same calling convention the VM's own opcodes use.
+pub type NativeFn = Box<dyn FnMut(&mut Vec<Value>) -> Result<(), PetrelError>>;
+
 pub struct VM {
     pub instructions: Vec<Operation>,
     pub constants: Vec<Value>,
     pub stack: Vec<Value>,
     pub ip: usize,
+    /// Consulted on a [`Trap`] instead of unwinding immediately. `None` means "abort", same as before.
+    pub trap_handler: Option<Box<dyn TrapHandler>>,
+    /// Capacity the value stack is bounded to; exceeding it is a `PetrelError::VMError(VMError::StackOverflow)`.
+    stack_size: usize,
+    /// Free-running count of dispatched instructions. Wraps on overflow rather than panicking or saturating,
+    /// so hosts can use it to meter or time-slice long-running programs.
+    pub instruction_count: u64,
+    /// Host functions registered with [`VM::register_native`], indexed by `OpCall`'s inline operand.
+    natives: Vec<(String, NativeFn)>,
+    /// Frames for in-progress `OpInvoke` calls, innermost last; empty at top level.
+    call_stack: Vec<CallFrame>,
+    /// Call depth `OpInvoke` is bounded to; exceeding it raises `VMError::StackOverflow`.
+    frame_limit: usize,
+}
+
+/// One in-progress `OpInvoke` call: where to resume `ip` on `OpReturn`, and
+/// the stack slot its arguments (and any locals the compiler allocates above
+/// them) start at; the callee's own value sits one slot below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallFrame {
+    return_ip: usize,
+    stack_base: usize,
+}
+
+/// Default number of nested `OpInvoke` calls allowed. Override with [`VM::with_frame_limit`].
+pub const FRAME_LIMIT: usize = 64;
+
+/// Outcome of a single [`VM::step`].
+enum StepResult {
+    Continue,
+    Returned,
+}
+
+/// Outcome of [`VM::run_bounded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunState {
+    /// The program ran to completion (hit `OpReturn`).
+    Finished,
+    /// The instruction budget ran out before the program finished; `consumed`
+    /// is how many instructions this call actually dispatched.
+    Suspended { consumed: u64 },
+}
+
+impl std::fmt::Debug for VM {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VM")
+            .field("instructions", &self.instructions)
+            .field("constants", &self.constants)
+            .field("stack", &self.stack)
+            .field("ip", &self.ip)
+            .field("trap_handler", &self.trap_handler.is_some())
+            .field("instruction_count", &self.instruction_count)
+            .field(
+                "natives",
+                &self
+                    .natives
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .field("call_stack", &self.call_stack)
+            .finish()
+    }
 }
 
 /// Macro for creating basic binary operations
 macro_rules! binary_op {
-    ($s:tt, $v:ident, $i:ident) => {
+    ($s:tt, $v:ident) => {
         {
             if let Value::Number(_) = $v.peek(0)? {
                 if let Value::Number(_) = $v.peek(1)? {
@@ -64,11 +113,32 @@ macro_rules! binary_op {
                     if let Value::Number(an) = $v.pop()? {
                         if let Value::Number(bn) = $v.pop()? {
                             // Push on the result
-                            $v.stack.push(Value::Number(bn $s an));
+                            $v.push(Value::Number(bn $s an))?;
                         }
                     }
                 } else {
-                    Err(VMError::Runtime(VM::create_context(&$i, "Operands must be numbers")))?;
+                    Err(VMError::Runtime($v.create_context_here("Operands must be numbers")))?;
+                }
+            }
+        }
+    };
+}
+
+/// Like `binary_op!`, but for the `Number, Number -> Bool` comparisons
+/// (`OpGreater`/`OpLess`); `OpEqual` handles its own cross-type semantics
+/// instead since it's expected to succeed on any pair of `Value` variants.
+macro_rules! compare_op {
+    ($s:tt, $v:ident) => {
+        {
+            if let Value::Number(_) = $v.peek(0)? {
+                if let Value::Number(_) = $v.peek(1)? {
+                    if let Value::Number(an) = $v.pop()? {
+                        if let Value::Number(bn) = $v.pop()? {
+                            $v.push(Value::Bool(bn $s an))?;
+                        }
+                    }
+                } else {
+                    Err(VMError::Runtime($v.create_context_here("Operands must be numbers")))?;
                 }
             }
         }
@@ -77,75 +147,334 @@ macro_rules! binary_op {
 
 impl VM {
     pub fn new() -> Self {
+        VM::with_stack_size(STACK_SIZE)
+    }
+
+    /// Create a VM whose value stack is bounded to `stack_size` entries
+    /// instead of the [`STACK_SIZE`] default.
+    pub fn with_stack_size(stack_size: usize) -> Self {
         VM {
             instructions: vec![],
             constants: vec![],
-            stack: vec![],
+            stack: Vec::with_capacity(stack_size),
             ip: 0,
+            trap_handler: None,
+            stack_size,
+            instruction_count: 0,
+            natives: Vec::new(),
+            call_stack: Vec::new(),
+            frame_limit: FRAME_LIMIT,
         }
     }
 
+    /// Bound nested `OpInvoke` calls to `frame_limit` instead of the
+    /// [`FRAME_LIMIT`] default.
+    pub fn with_frame_limit(mut self, frame_limit: usize) -> Self {
+        self.frame_limit = frame_limit;
+        self
+    }
+
+    /// Install a handler consulted whenever the VM hits a recoverable [`Trap`]
+    /// instead of unwinding with a `PetrelError`.
+    pub fn set_trap_handler(&mut self, handler: Box<dyn TrapHandler>) {
+        self.trap_handler = Some(handler);
+    }
+
+    /// Register a host function reachable from Petrel code, returning the
+    /// index an `OpCall` operand refers to it by. `name` is kept only for
+    /// the disassembler; the VM dispatches purely by index.
+    pub fn register_native(&mut self, name: impl Into<String>, f: NativeFn) -> u8 {
+        self.natives.push((name.into(), f));
+        (self.natives.len() - 1) as u8
+    }
+
+    /// Name of the native registered at `index`, for the disassembler.
+    pub fn native_name(&self, index: u8) -> Option<&str> {
+        self.natives
+            .get(index as usize)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Push `value` onto the stack, failing with `VMError::StackOverflow`
+    /// once `stack_size` entries are already on it.
+    fn push(&mut self, value: Value) -> Result<(), PetrelError> {
+        if self.stack.len() >= self.stack_size {
+            Err(VMError::StackOverflow)?;
+        }
+        self.stack.push(value);
+        Ok(())
+    }
+
     pub fn run(&mut self, stack_trace: bool) -> Result<(), PetrelError> {
         loop {
-            if stack_trace {
-                for val in &self.stack {
-                    println!("{:>10}[ {:?} ]", " ", val);
-                }
+            if let StepResult::Returned = self.step(stack_trace)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run at most `budget` instructions before returning, so a host can
+    /// time-slice or cooperatively schedule long-running programs. Call
+    /// again with the same `VM` to pick up where it left off — `self.ip` and
+    /// `self.stack` are left exactly as they were at suspension.
+    pub fn run_bounded(
+        &mut self,
+        mut budget: u64,
+        stack_trace: bool,
+    ) -> Result<RunState, PetrelError> {
+        let mut consumed = 0u64;
+        while budget > 0 {
+            if let StepResult::Returned = self.step(stack_trace)? {
+                return Ok(RunState::Finished);
             }
-            let instruction = self.instructions.get(self.ip).ok_or(VMError::NoReturn)?;
-            dissasemble_instruction(self, self.ip);
+            consumed += 1;
+            budget -= 1;
+        }
+        Ok(RunState::Suspended { consumed })
+    }
+
+    /// Execute a single instruction. Returns `StepResult::Returned` once
+    /// `OpReturn` is hit, otherwise `StepResult::Continue`.
+    fn step(&mut self, stack_trace: bool) -> Result<StepResult, PetrelError> {
+        #[cfg(feature = "std")]
+        if stack_trace {
+            for val in &self.stack {
+                println!("{:>10}[ {:?} ]", " ", val);
+            }
+        }
+        let opcode_byte = self
+            .instructions
+            .get(self.ip)
+            .ok_or(VMError::NoReturn)?
+            .opcode;
+        #[cfg(feature = "std")]
+        dissasemble_instruction(self, self.ip);
+
+        self.instruction_count = self.instruction_count.wrapping_add(1);
+
+        let opcode = match Opcode::try_from(opcode_byte) {
+            Ok(op) => op,
+            Err(_) => match self.handle_trap(Trap::InvalidOpcode(opcode_byte))? {
+                Some(v) => {
+                    self.push(v)?;
+                    self.ip += 1;
+                    return Ok(StepResult::Continue);
+                }
+                None => {
+                    self.ip += 1;
+                    return Ok(StepResult::Continue);
+                }
+            },
+        };
+
+        {
             use Opcode::*;
-            match Opcode::try_from(instruction.opcode)? {
-                OpReturn => break,
-                OpAdd => binary_op!(+, self, instruction),
-                OpSubtract => binary_op!(-, self, instruction),
-                OpMultiply => binary_op!(*, self, instruction),
-                OpDivide => binary_op!(/, self, instruction),
+            match opcode {
+                OpReturn => match self.call_stack.pop() {
+                    // Returning from an `OpInvoke` call: the result replaces
+                    // everything the call pushed (its own value, args, and
+                    // locals), and execution resumes where it left off.
+                    Some(frame) => {
+                        let result = self.pop()?;
+                        self.stack.truncate(frame.stack_base - 1);
+                        self.push(result)?;
+                        self.ip = frame.return_ip;
+                        return Ok(StepResult::Continue);
+                    }
+                    // The outermost return ends the program.
+                    None => return Ok(StepResult::Returned),
+                },
+                OpAdd => binary_op!(+, self),
+                OpSubtract => binary_op!(-, self),
+                OpMultiply => binary_op!(*, self),
+                OpGreater => compare_op!(>, self),
+                OpLess => compare_op!(<, self),
+                OpEqual => {
+                    let b = self.pop()?;
+                    let a = self.pop()?;
+                    let eq = match (a, b) {
+                        (Value::Number(x), Value::Number(y)) => x == y,
+                        (Value::Bool(x), Value::Bool(y)) => x == y,
+                        (Value::Null, Value::Null) => true,
+                        // Mismatched variants (e.g. `1 == true`) are never
+                        // equal, not a runtime error.
+                        _ => false,
+                    };
+                    self.push(Value::Bool(eq))?;
+                }
+                OpDivide => {
+                    if let Value::Number(_) = self.peek(0)? {
+                        if let Value::Number(_) = self.peek(1)? {
+                            if let Value::Number(divisor) = self.pop()? {
+                                if let Value::Number(dividend) = self.pop()? {
+                                    if divisor == 0.0 {
+                                        if let Some(v) = self.handle_trap(Trap::DivideByZero)? {
+                                            self.push(v)?;
+                                        }
+                                    } else {
+                                        self.push(Value::Number(dividend / divisor))?;
+                                    }
+                                }
+                            }
+                        } else {
+                            Err(VMError::Runtime(
+                                self.create_context_here("Operands must be numbers"),
+                            ))?;
+                        }
+                    }
+                }
                 OpNegate => {
                     if let Value::Number(_) = self.peek(0)? {
                         // Actually pop the value off the stack
                         if let Value::Number(n) = self.pop()? {
                             // Add the negated value to the stack
-                            self.stack.push(Value::Number(-n));
+                            self.push(Value::Number(-n))?;
                         }
                     } else {
                         // Error out
-                        Err(VMError::Runtime(Self::create_context(
-                            instruction,
-                            "Attempted to negate a non number",
-                        )))?;
+                        Err(VMError::Runtime(
+                            self.create_context_here("Attempted to negate a non number"),
+                        ))?;
                     }
                 }
                 OpConstant => {
                     let val =
                         self.constants[self.instructions[self.ip + 1].opcode as usize].clone();
-                    self.stack.push(val);
-                    self.ip += 1;
+                    self.push(val)?;
+                    self.ip += OpConstant.operand_len();
+                }
+                OpConstantLong => {
+                    let hi = self.instructions[self.ip + 1].opcode as usize;
+                    let mid = self.instructions[self.ip + 2].opcode as usize;
+                    let lo = self.instructions[self.ip + 3].opcode as usize;
+                    let index = (hi << 16) | (mid << 8) | lo;
+                    let val = self.constants[index].clone();
+                    self.push(val)?;
+                    self.ip += OpConstantLong.operand_len();
+                }
+                OpCall => {
+                    let index = self.instructions[self.ip + 1].opcode;
+                    self.ip += OpCall.operand_len();
+                    if index as usize >= self.natives.len() {
+                        if let Some(v) = self.handle_trap(Trap::UnknownNative(index as usize))? {
+                            self.push(v)?;
+                        }
+                    } else {
+                        let (_, native) = &mut self.natives[index as usize];
+                        native(&mut self.stack)?;
+                    }
+                }
+                OpInvoke => {
+                    let argc = self.instructions[self.ip + 1].opcode as usize;
+
+                    if self.call_stack.len() >= self.frame_limit {
+                        Err(VMError::StackOverflow)?;
+                    }
+
+                    // Locals (the args themselves, to start) begin here; the
+                    // callee's own value sits one slot further down.
+                    let stack_base = self
+                        .stack
+                        .len()
+                        .checked_sub(argc)
+                        .ok_or(VMError::EmptyStack)?;
+                    let callee_slot = stack_base.checked_sub(1).ok_or(VMError::EmptyStack)?;
+
+                    // A `Value::Native` callee has no bytecode to jump into: it
+                    // runs to completion immediately instead of pushing a
+                    // `CallFrame`, the same calling convention `OpCall` uses.
+                    if let Some(Value::Native(index)) = self.stack.get(callee_slot) {
+                        let index = *index;
+                        if index >= self.natives.len() {
+                            if let Some(v) = self.handle_trap(Trap::UnknownNative(index))? {
+                                self.stack.truncate(callee_slot);
+                                self.push(v)?;
+                            } else {
+                                self.stack.truncate(callee_slot);
+                            }
+                        } else {
+                            let (_, native) = &mut self.natives[index];
+                            native(&mut self.stack)?;
+                            let result = self.pop()?;
+                            self.stack.truncate(callee_slot);
+                            self.push(result)?;
+                        }
+                        self.ip += 2;
+                        return Ok(StepResult::Continue);
+                    }
+
+                    let entry = match self.stack.get(callee_slot) {
+                        Some(Value::Function(entry)) => *entry,
+                        _ => Err(VMError::Runtime(
+                            self.create_context_here("Attempted to call a non-function value"),
+                        ))?,
+                    };
+
+                    self.call_stack.push(CallFrame {
+                        return_ip: self.ip + 2,
+                        stack_base,
+                    });
+                    self.ip = entry;
+                    return Ok(StepResult::Continue);
                 }
-                OpNull => self.stack.push(Value::Null),
-                OpTrue => self.stack.push(Value::Bool(true)),
-                OpFalse => self.stack.push(Value::Bool(false)),
+                OpNull => self.push(Value::Null)?,
+                OpTrue => self.push(Value::Bool(true))?,
+                OpFalse => self.push(Value::Bool(false))?,
                 OpNot => {
                     // Check if it is a bool
                     match self.peek(0)? {
                         Value::Bool(_) => {
                             // Use logical not
                             if let Value::Bool(b) = self.pop()? {
-                                self.stack.push(Value::Bool(!b));
+                                self.push(Value::Bool(!b))?;
                             }
                         }
                         // !null == null so we do nothing
                         Value::Null => {}
-                        _ => Err(VMError::Runtime(Self::create_context(
-                            instruction,
+                        _ => Err(VMError::Runtime(self.create_context_here(
                             "Attempted to use logical not on a non boolean",
                         )))?,
                     }
                 }
+                OpJump => {
+                    let offset = self.read_jump_offset();
+                    self.ip += 3 + offset;
+                    return Ok(StepResult::Continue);
+                }
+                OpJumpIfFalse => {
+                    let offset = self.read_jump_offset();
+                    let falsey = matches!(self.peek(0)?, Value::Bool(false) | Value::Null);
+                    self.ip += if falsey { 3 + offset } else { 3 };
+                    return Ok(StepResult::Continue);
+                }
+                OpLoop => {
+                    let offset = self.read_jump_offset();
+                    self.ip -= offset;
+                    return Ok(StepResult::Continue);
+                }
             }
-            self.ip += 1;
         }
-        Ok(())
+        self.ip += 1;
+        Ok(StepResult::Continue)
+    }
+
+    /// Decode the 16-bit jump distance spread across the two `Operation`
+    /// slots immediately after the jump opcode at `self.ip`.
+    fn read_jump_offset(&self) -> usize {
+        let hi = self.instructions[self.ip + 1].opcode;
+        let lo = self.instructions[self.ip + 2].opcode;
+        (((hi as u16) << 8) | lo as u16) as usize
+    }
+
+    /// Backpatch the 16-bit operand of the jump opcode written at
+    /// `jump_addr` with the distance to the current end of `instructions`,
+    /// i.e. "jump to here". Lets a compiler emit a placeholder offset before
+    /// the branch target is known and fix it up once it is.
+    pub fn patch_jump(&mut self, jump_addr: usize) {
+        let offset = self.instructions.len() - jump_addr - 3;
+        self.instructions[jump_addr + 1].opcode = ((offset >> 8) & 0xff) as u8;
+        self.instructions[jump_addr + 2].opcode = (offset & 0xff) as u8;
     }
 
     /// Peek at the opcode distance from the top of the stack. Use 0 for top
@@ -158,25 +487,217 @@ impl VM {
         Ok(v)
     }
 
+    /// Pop the top of the stack, consulting the [`TrapHandler`] on underflow
+    /// instead of failing outright. A `Resume` action yields `Value::Null` so
+    /// the interpreter always has something to work with.
     fn pop(&mut self) -> Result<Value, PetrelError> {
-        #[allow(clippy::unnecessary_lazy_evaluations)]
-        let v = self.stack.pop().ok_or_else(|| VMError::EmptyStack)?;
-        Ok(v)
+        match self.stack.pop() {
+            Some(v) => Ok(v),
+            None => Ok(self
+                .handle_trap(Trap::StackUnderflow)?
+                .unwrap_or(Value::Null)),
+        }
     }
 
-    fn create_context(ins: &Operation, message: &str) -> Context {
-        Context::new("Unknown".to_string(), ins.line, message.to_string())
+    /// Consult the installed [`TrapHandler`] about `trap`. Returns `Ok(None)`
+    /// on `Resume`, `Ok(Some(value))` on `Supply`, and the equivalent
+    /// `PetrelError` on `Abort` or when no handler is installed.
+    fn handle_trap(&mut self, trap: Trap) -> Result<Option<Value>, PetrelError> {
+        let action = match &mut self.trap_handler {
+            Some(handler) => {
+                let state = VMState {
+                    ip: self.ip,
+                    stack: &self.stack,
+                    constants: &self.constants,
+                };
+                handler.handle(&state, trap.clone())
+            }
+            None => TrapAction::Abort,
+        };
+
+        match action {
+            TrapAction::Abort => Err(Self::trap_to_error(trap)),
+            TrapAction::Resume => Ok(None),
+            TrapAction::Supply(v) => Ok(Some(v)),
+        }
     }
 
-    pub fn write_constant(&mut self, constant: Value) -> u8 {
+    fn trap_to_error(trap: Trap) -> PetrelError {
+        match trap {
+            Trap::DivideByZero => VMError::DivideByZero.into(),
+            Trap::StackUnderflow => VMError::EmptyStack.into(),
+            Trap::InvalidOpcode(b) => VMError::InvalidOpcodeConversion(b).into(),
+            Trap::MemoryFault { addr } => VMError::MemoryFault { addr }.into(),
+            Trap::UnknownNative(index) => VMError::UnknownNative(index).into(),
+        }
+    }
+
+    fn create_context_here(&self, message: &str) -> Context {
+        let line = self.instructions.get(self.ip).map(|i| i.line).unwrap_or(0);
+        Context::new("Unknown".to_string(), line, message.to_string())
+    }
+
+    pub fn write_constant(&mut self, constant: Value) -> usize {
         self.constants.push(constant);
-        (self.constants.len() - 1) as u8
+        self.constants.len() - 1
+    }
+
+    /// Add a constant and emit the instruction that reads it back: a plain
+    /// `OpConstant` for the first 256 constants, or `OpConstantLong`'s 24-bit
+    /// operand once a chunk grows past that ceiling.
+    pub fn write_constant_op(&mut self, constant: Value, line: usize) {
+        let index = self.write_constant(constant);
+        if let Ok(index) = u8::try_from(index) {
+            self.write_operation(Opcode::OpConstant.into(), line);
+            self.write_operation(index, line);
+        } else {
+            self.write_operation(Opcode::OpConstantLong.into(), line);
+            self.write_operation(((index >> 16) & 0xff) as u8, line);
+            self.write_operation(((index >> 8) & 0xff) as u8, line);
+            self.write_operation((index & 0xff) as u8, line);
+        }
     }
 
     pub fn write_operation(&mut self, code: u8, line: usize) {
         let op = Operation { opcode: code, line };
         self.instructions.push(op);
     }
+
+    /// Persist the instruction stream, line table, and constant pool to a
+    /// compact binary chunk (a `.ptrlc` artifact), so a `VM` can be shipped
+    /// and [`VM::deserialize`]d without recompiling from source.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(BYTECODE_MAGIC);
+        out.push(BYTECODE_VERSION);
+
+        out.extend_from_slice(&(self.instructions.len() as u32).to_le_bytes());
+        for op in &self.instructions {
+            out.push(op.opcode);
+            out.extend_from_slice(&(op.line as u64).to_le_bytes());
+        }
+
+        out.extend_from_slice(&(self.constants.len() as u32).to_le_bytes());
+        for constant in &self.constants {
+            match constant {
+                Value::Number(n) => {
+                    out.push(0);
+                    out.extend_from_slice(&n.to_le_bytes());
+                }
+                Value::Bool(b) => {
+                    out.push(1);
+                    out.push(*b as u8);
+                }
+                Value::Null => out.push(2),
+                Value::Function(entry) => {
+                    out.push(3);
+                    out.extend_from_slice(&(*entry as u64).to_le_bytes());
+                }
+                Value::Native(index) => {
+                    out.push(4);
+                    out.extend_from_slice(&(*index as u64).to_le_bytes());
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Load a chunk written by [`VM::serialize`]. Fails with
+    /// `VMError::BadBytecode` on a truncated/malformed chunk, or
+    /// `VMError::VersionMismatch` when the chunk predates or postdates the
+    /// format this build of the VM understands.
+    pub fn deserialize(bytes: &[u8]) -> Result<VM, PetrelError> {
+        let mut cursor = 0usize;
+
+        if bytes_at(bytes, &mut cursor, BYTECODE_MAGIC.len())? != BYTECODE_MAGIC {
+            Err(VMError::BadBytecode("missing magic header"))?;
+        }
+
+        let version = byte_at(bytes, &mut cursor)?;
+        if version != BYTECODE_VERSION {
+            Err(VMError::VersionMismatch {
+                expected: BYTECODE_VERSION,
+                found: version,
+            })?;
+        }
+
+        let mut vm = VM::new();
+
+        let instruction_count = u32_at(bytes, &mut cursor)?;
+        for _ in 0..instruction_count {
+            let opcode = byte_at(bytes, &mut cursor)?;
+            let line = u64_at(bytes, &mut cursor)? as usize;
+            vm.instructions.push(Operation { opcode, line });
+        }
+
+        let constant_count = u32_at(bytes, &mut cursor)?;
+        for _ in 0..constant_count {
+            let constant = match byte_at(bytes, &mut cursor)? {
+                0 => Value::Number(f64_at(bytes, &mut cursor)?),
+                1 => Value::Bool(byte_at(bytes, &mut cursor)? != 0),
+                2 => Value::Null,
+                3 => Value::Function(u64_at(bytes, &mut cursor)? as usize),
+                4 => Value::Native(u64_at(bytes, &mut cursor)? as usize),
+                _ => Err(VMError::BadBytecode("unknown constant tag"))?,
+            };
+            vm.constants.push(constant);
+        }
+
+        Ok(vm)
+    }
+
+    /// Render the instruction stream as a human-readable, persistable
+    /// listing: a `section[text]` header followed by one `address mnemonic`
+    /// line per instruction (operand bytes are folded into their owning
+    /// instruction's line rather than printed as instructions of their own).
+    pub fn disassemble_to_string(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "section[text]");
+
+        let mut offset = 0;
+        while offset < self.instructions.len() {
+            let _ = writeln!(out, "{}", format_instruction(self, offset));
+            let operand_len = Opcode::try_from(self.instructions[offset].opcode)
+                .map(|op| op.operand_len())
+                .unwrap_or(0);
+            offset += 1 + operand_len;
+        }
+
+        out
+    }
+}
+
+/// Magic header identifying a Petrel bytecode chunk.
+const BYTECODE_MAGIC: &[u8; 4] = b"PTRC";
+/// Bump this when [`VM::serialize`]'s on-disk layout changes incompatibly.
+const BYTECODE_VERSION: u8 = 1;
+
+fn byte_at(bytes: &[u8], cursor: &mut usize) -> Result<u8, PetrelError> {
+    Ok(bytes_at(bytes, cursor, 1)?[0])
+}
+
+fn bytes_at<'a>(bytes: &'a [u8], cursor: &mut usize, len: usize) -> Result<&'a [u8], PetrelError> {
+    let slice = bytes
+        .get(*cursor..*cursor + len)
+        .ok_or(VMError::BadBytecode("unexpected end of chunk"))?;
+    *cursor += len;
+    Ok(slice)
+}
+
+fn u32_at(bytes: &[u8], cursor: &mut usize) -> Result<u32, PetrelError> {
+    let slice = bytes_at(bytes, cursor, 4)?;
+    Ok(u32::from_le_bytes(slice.try_into().unwrap()))
+}
+
+fn u64_at(bytes: &[u8], cursor: &mut usize) -> Result<u64, PetrelError> {
+    let slice = bytes_at(bytes, cursor, 8)?;
+    Ok(u64::from_le_bytes(slice.try_into().unwrap()))
+}
+
+fn f64_at(bytes: &[u8], cursor: &mut usize) -> Result<f64, PetrelError> {
+    let slice = bytes_at(bytes, cursor, 8)?;
+    Ok(f64::from_le_bytes(slice.try_into().unwrap()))
 }
 
 impl Default for VM {
@@ -198,18 +719,354 @@ mod vm_test {
     #[test]
     fn basic_arithmatic() {
         let mut vm = VM::new();
-        let a = vm.write_constant(Value::Number(2.5));
-        let b = vm.write_constant(Value::Number(7.5));
-        let c = vm.write_constant(Value::Number(2.0));
-        vm.write_operation(Opcode::OpConstant.into(), 123);
-        vm.write_operation(a, 123);
-        vm.write_operation(Opcode::OpConstant.into(), 123);
-        vm.write_operation(b, 123);
+        vm.write_constant_op(Value::Number(2.5), 123);
+        vm.write_constant_op(Value::Number(7.5), 123);
         vm.write_operation(Opcode::OpAdd.into(), 123);
-        vm.write_operation(Opcode::OpConstant.into(), 123);
-        vm.write_operation(c, 123);
+        vm.write_constant_op(Value::Number(2.0), 123);
         vm.write_operation(Opcode::OpDivide.into(), 123);
         vm.write_operation(Opcode::OpReturn.into(), 123);
         vm.run(true).unwrap();
     }
+
+    #[test]
+    fn run_bounded_suspends_and_resumes() {
+        let mut vm = VM::new();
+        vm.write_constant_op(Value::Number(1.0), 1);
+        vm.write_constant_op(Value::Number(2.0), 1);
+        vm.write_operation(Opcode::OpAdd.into(), 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        // Budget of 1 only covers the first `OpConstant` + its operand.
+        let state = vm.run_bounded(1, false).unwrap();
+        assert_eq!(state, RunState::Suspended { consumed: 1 });
+        assert_eq!(vm.instruction_count, 1);
+
+        // Resuming with plenty of budget runs it to completion.
+        let state = vm.run_bounded(10, false).unwrap();
+        assert_eq!(state, RunState::Finished);
+        assert!(matches!(vm.stack.last(), Some(Value::Number(n)) if *n == 3.0));
+    }
+
+    #[test]
+    fn op_call_invokes_a_registered_native() {
+        let mut vm = VM::new();
+        let double = vm.register_native(
+            "double",
+            Box::new(|stack| {
+                if let Some(Value::Number(n)) = stack.pop() {
+                    stack.push(Value::Number(n * 2.0));
+                }
+                Ok(())
+            }),
+        );
+
+        vm.write_constant_op(Value::Number(21.0), 1);
+        vm.write_operation(Opcode::OpCall.into(), 1);
+        vm.write_operation(double, 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        assert!(matches!(vm.stack.last(), Some(Value::Number(n)) if *n == 42.0));
+    }
+
+    #[test]
+    fn op_call_with_unregistered_index_aborts() {
+        let mut vm = VM::new();
+        vm.write_operation(Opcode::OpCall.into(), 1);
+        vm.write_operation(0, 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        assert!(vm.run(false).is_err());
+    }
+
+    #[test]
+    fn op_jump_skips_over_instructions() {
+        let mut vm = VM::new();
+
+        vm.write_operation(Opcode::OpJump.into(), 1);
+        let jump_addr = vm.instructions.len() - 1;
+        vm.write_operation(0, 1);
+        vm.write_operation(0, 1);
+
+        vm.write_constant_op(Value::Number(1.0), 1); // skipped
+
+        vm.patch_jump(jump_addr);
+
+        vm.write_constant_op(Value::Number(2.0), 1); // kept
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        assert_eq!(vm.stack.len(), 1);
+        assert!(matches!(vm.stack.last(), Some(Value::Number(n)) if *n == 2.0));
+    }
+
+    #[test]
+    fn op_jump_if_false_skips_the_body_on_a_falsey_condition() {
+        let mut vm = VM::new();
+
+        vm.write_operation(Opcode::OpFalse.into(), 1);
+        vm.write_operation(Opcode::OpJumpIfFalse.into(), 1);
+        let jump_addr = vm.instructions.len() - 1;
+        vm.write_operation(0, 1);
+        vm.write_operation(0, 1);
+
+        vm.write_constant_op(Value::Number(99.0), 1); // body
+
+        vm.patch_jump(jump_addr);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        // `OpJumpIfFalse` peeks rather than pops, so the condition is still
+        // on the stack; the body's constant was never pushed.
+        assert!(matches!(vm.stack.last(), Some(Value::Bool(false))));
+        assert_eq!(vm.stack.len(), 1);
+    }
+
+    #[test]
+    fn op_loop_jumps_backward() {
+        let mut vm = VM::new();
+        vm.write_operation(Opcode::OpNull.into(), 1);
+        let loop_start = vm.instructions.len() - 1;
+        vm.write_operation(Opcode::OpLoop.into(), 1);
+        let loop_addr = vm.instructions.len() - 1;
+        let offset = loop_addr - loop_start;
+        vm.write_operation(((offset >> 8) & 0xff) as u8, 1);
+        vm.write_operation((offset & 0xff) as u8, 1);
+
+        // A budget of 2 covers exactly one `OpNull` + `OpLoop` round trip.
+        let state = vm.run_bounded(2, false).unwrap();
+        assert_eq!(state, RunState::Suspended { consumed: 2 });
+        assert_eq!(vm.ip, loop_start);
+    }
+
+    #[test]
+    fn op_greater_and_op_less_compare_numbers() {
+        let mut vm = VM::new();
+        vm.write_constant_op(Value::Number(3.0), 1);
+        vm.write_constant_op(Value::Number(7.0), 1);
+        vm.write_operation(Opcode::OpLess.into(), 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        assert!(matches!(vm.stack.last(), Some(Value::Bool(true))));
+    }
+
+    #[test]
+    fn op_equal_is_false_across_mismatched_variants() {
+        let mut vm = VM::new();
+        vm.write_constant_op(Value::Number(1.0), 1);
+        vm.write_operation(Opcode::OpTrue.into(), 1);
+        vm.write_operation(Opcode::OpEqual.into(), 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        assert!(matches!(vm.stack.last(), Some(Value::Bool(false))));
+    }
+
+    #[test]
+    fn op_equal_compares_same_variant_by_value() {
+        let mut vm = VM::new();
+        vm.write_constant_op(Value::Number(5.0), 1);
+        vm.write_constant_op(Value::Number(5.0), 1);
+        vm.write_operation(Opcode::OpEqual.into(), 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        assert!(matches!(vm.stack.last(), Some(Value::Bool(true))));
+    }
+
+    #[test]
+    fn op_invoke_calls_a_function_and_returns_to_the_caller() {
+        let mut vm = VM::new();
+
+        // The function body lives at instruction 0: push 42 and return.
+        vm.write_constant_op(Value::Number(42.0), 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+        let func_entry = 0;
+
+        // Main pushes the callee's own value, invokes it with no args, then returns.
+        let main_entry = vm.instructions.len();
+        vm.write_constant_op(Value::Function(func_entry), 2);
+        vm.write_operation(Opcode::OpInvoke.into(), 2);
+        vm.write_operation(0, 2);
+        vm.write_operation(Opcode::OpReturn.into(), 2);
+
+        // Start at main, not at the function body at instruction 0.
+        vm.ip = main_entry;
+        vm.run(false).unwrap();
+        assert_eq!(vm.stack.len(), 1);
+        assert!(matches!(vm.stack.last(), Some(Value::Number(n)) if *n == 42.0));
+    }
+
+    #[test]
+    fn op_invoke_calls_a_native_value_without_pushing_a_call_frame() {
+        let mut vm = VM::new();
+        let double = vm.register_native(
+            "double",
+            Box::new(|stack| {
+                if let Some(Value::Number(n)) = stack.pop() {
+                    stack.push(Value::Number(n * 2.0));
+                }
+                Ok(())
+            }),
+        );
+
+        vm.write_constant_op(Value::Native(double as usize), 1);
+        vm.write_constant_op(Value::Number(21.0), 1);
+        vm.write_operation(Opcode::OpInvoke.into(), 1);
+        vm.write_operation(1, 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        assert_eq!(vm.stack.len(), 1);
+        assert!(matches!(vm.stack.last(), Some(Value::Number(n)) if *n == 42.0));
+    }
+
+    #[test]
+    fn op_invoke_past_the_frame_limit_is_a_stack_overflow() {
+        let mut vm = VM::new().with_frame_limit(1);
+
+        // A function that immediately invokes itself again, recursing forever.
+        vm.write_constant_op(Value::Function(0), 1);
+        vm.write_operation(Opcode::OpInvoke.into(), 1);
+        vm.write_operation(0, 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        assert!(vm.run(false).is_err());
+    }
+
+    #[test]
+    fn op_invoke_with_unregistered_native_aborts_without_a_trap_handler() {
+        let mut vm = VM::new();
+        // Nothing is registered at index 0, so this `Value::Native` is unknown.
+        vm.write_constant_op(Value::Native(0), 1);
+        vm.write_operation(Opcode::OpInvoke.into(), 1);
+        vm.write_operation(0, 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        assert!(vm.run(false).is_err());
+    }
+
+    /// A trap handler that always returns the same fixed `TrapAction`.
+    struct FixedTrapHandler(TrapAction);
+
+    impl TrapHandler for FixedTrapHandler {
+        fn handle(&mut self, _vm: &VMState, _trap: Trap) -> TrapAction {
+            match &self.0 {
+                TrapAction::Abort => TrapAction::Abort,
+                TrapAction::Resume => TrapAction::Resume,
+                TrapAction::Supply(v) => TrapAction::Supply(v.clone()),
+            }
+        }
+    }
+
+    #[test]
+    fn op_invoke_with_unregistered_native_resumes_with_a_clean_stack() {
+        let mut vm = VM::new();
+        vm.set_trap_handler(Box::new(FixedTrapHandler(TrapAction::Resume)));
+
+        // Callee (unknown native) plus one arg, both of which must be gone
+        // from the stack afterwards, not just left underneath nothing.
+        vm.write_constant_op(Value::Native(0), 1);
+        vm.write_constant_op(Value::Number(1.0), 1);
+        vm.write_operation(Opcode::OpInvoke.into(), 1);
+        vm.write_operation(1, 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        assert_eq!(vm.stack.len(), 0);
+    }
+
+    #[test]
+    fn op_invoke_with_unregistered_native_supplies_a_value_on_a_clean_stack() {
+        let mut vm = VM::new();
+        vm.set_trap_handler(Box::new(FixedTrapHandler(TrapAction::Supply(
+            Value::Number(99.0),
+        ))));
+
+        vm.write_constant_op(Value::Native(0), 1);
+        vm.write_constant_op(Value::Number(1.0), 1);
+        vm.write_operation(Opcode::OpInvoke.into(), 1);
+        vm.write_operation(1, 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        vm.run(false).unwrap();
+        // The callee and its arg are gone; only the supplied value remains.
+        assert_eq!(vm.stack.len(), 1);
+        assert!(matches!(vm.stack.last(), Some(Value::Number(n)) if *n == 99.0));
+    }
+
+    #[test]
+    fn serialize_then_deserialize_round_trips_instructions_and_constants() {
+        let mut vm = VM::new();
+        vm.write_constant_op(Value::Number(2.5), 1);
+        vm.write_constant_op(Value::Bool(true), 1);
+        vm.write_constant_op(Value::Null, 1);
+        vm.write_constant_op(Value::Function(7), 1);
+        vm.write_constant_op(Value::Native(3), 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        let bytes = vm.serialize();
+        let restored = VM::deserialize(&bytes).unwrap();
+
+        assert_eq!(restored.instructions.len(), vm.instructions.len());
+        for (left, right) in restored.instructions.iter().zip(vm.instructions.iter()) {
+            assert_eq!(left.opcode, right.opcode);
+            assert_eq!(left.line, right.line);
+        }
+        assert!(matches!(restored.constants[0], Value::Number(n) if n == 2.5));
+        assert!(matches!(restored.constants[1], Value::Bool(true)));
+        assert!(matches!(restored.constants[2], Value::Null));
+        assert!(matches!(restored.constants[3], Value::Function(7)));
+        assert!(matches!(restored.constants[4], Value::Native(3)));
+    }
+
+    #[test]
+    fn deserialize_rejects_bad_magic_and_version_mismatch() {
+        let vm = VM::new();
+        let bytes = vm.serialize();
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'X';
+        assert!(matches!(
+            VM::deserialize(&wrong_magic),
+            Err(PetrelError::VMError(VMError::BadBytecode(_)))
+        ));
+
+        let mut wrong_version = bytes;
+        wrong_version[4] = BYTECODE_VERSION + 1;
+        assert!(matches!(
+            VM::deserialize(&wrong_version),
+            Err(PetrelError::VMError(VMError::VersionMismatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn disassemble_to_string_advances_past_operand_bytes() {
+        let mut vm = VM::new();
+        vm.write_constant_op(Value::Number(1.0), 1);
+        vm.write_operation(Opcode::OpReturn.into(), 2);
+
+        let dump = vm.disassemble_to_string();
+        assert!(dump.starts_with("section[text]\n"));
+        // The header plus exactly one line per real instruction (2), not one
+        // per raw slot (3).
+        assert_eq!(dump.lines().count(), 3);
+        assert!(dump.contains("Constant"));
+        assert!(dump.contains("Return"));
+    }
+
+    #[test]
+    fn write_constant_op_emits_op_constant_long_past_256_constants() {
+        let mut vm = VM::new();
+        for i in 0..300 {
+            vm.write_constant(Value::Number(i as f64));
+        }
+        vm.write_constant_op(Value::Number(42.0), 1);
+        vm.write_operation(Opcode::OpReturn.into(), 1);
+
+        assert_eq!(vm.instructions[0].opcode, u8::from(Opcode::OpConstantLong));
+        vm.run(false).unwrap();
+        assert!(matches!(vm.stack.last(), Some(Value::Number(n)) if *n == 42.0));
+    }
 }